@@ -0,0 +1,175 @@
+//  STORAGE.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:06:32
+//  Last edited:
+//    16 Oct 2026, 00:28:34
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Benchmarks the component storage of the Ecs (iteration, joins,
+//!   spawn/despawn churn and command application) at various entity
+//!   counts, so storage redesigns can be compared on numbers.
+// 
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use rust_ecs::{query, CommandBuffer, Component, Ecs, Entity, EntityReserver};
+
+
+/***** CONSTANTS *****/
+/// The entity counts to run every benchmark at.
+const SIZES: [usize; 3] = [ 10_000, 100_000, 1_000_000 ];
+
+
+
+
+
+/***** COMPONENTS *****/
+/// A position in 2D space, which every entity has.
+#[derive(Clone, Copy, Debug)]
+struct Position(f32, f32);
+impl Component for Position {}
+
+/// A velocity in 2D space, which every other entity has.
+#[derive(Clone, Copy, Debug)]
+struct Velocity(f32, f32);
+impl Component for Velocity {}
+
+/// A health value, which every fourth entity has.
+#[derive(Clone, Copy, Debug)]
+struct Health(u32);
+impl Component for Health {}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Creates a new Ecs with the given number of entities.
+/// 
+/// Every entity has a Position, every other entity a Velocity and every fourth entity a Health, so joins have to skip entities.
+/// 
+/// **Arguments**
+///  * `n`: The number of entities to spawn.
+/// 
+/// **Returns**  
+/// The new Ecs.
+fn populate(n: usize) -> Rc<RefCell<Ecs>> {
+    let ecs = Ecs::new(n);
+    Ecs::register::<Position>(&ecs);
+    Ecs::register::<Velocity>(&ecs);
+    Ecs::register::<Health>(&ecs);
+    {
+        let ecs = ecs.borrow();
+        for i in 0..n {
+            let entity: Entity = ecs.add_entity();
+            ecs.add_component(entity, Position(i as f32, 0.0));
+            if i % 2 == 0 { ecs.add_component(entity, Velocity(1.0, 1.0)); }
+            if i % 4 == 0 { ecs.add_component(entity, Health(100)); }
+        }
+    }
+    ecs
+}
+
+
+
+
+
+/***** BENCHMARKS *****/
+/// Iterates over a single component list.
+fn bench_iter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iter");
+    for n in SIZES {
+        let ecs = populate(n);
+        let ecs = ecs.borrow();
+        group.bench_with_input(BenchmarkId::new("for_each_mut", n), &n, |b, _| {
+            b.iter(|| ecs.for_each_mut::<Position>(|_, pos| { pos.0 += 1.0; }));
+        });
+        group.bench_with_input(BenchmarkId::new("list_iter", n), &n, |b, _| {
+            b.iter(|| black_box(ecs.list_component::<Position>().iter().map(|pos| pos.0).sum::<f32>()));
+        });
+    }
+    group.finish();
+}
+
+/// Joins two and three component lists using `query!`.
+fn bench_join(c: &mut Criterion) {
+    let mut group = c.benchmark_group("join");
+    for n in SIZES {
+        let ecs = populate(n);
+        let ecs = ecs.borrow();
+        group.bench_with_input(BenchmarkId::new("two_way", n), &n, |b, _| {
+            b.iter(|| query!(ecs, |pos: &mut Position, vel: &Velocity| {
+                pos.0 += vel.0;
+                pos.1 += vel.1;
+            }));
+        });
+        group.bench_with_input(BenchmarkId::new("three_way", n), &n, |b, _| {
+            b.iter(|| query!(ecs, |pos: &mut Position, vel: &Velocity, health: &Health| {
+                pos.0 += vel.0 * health.0 as f32;
+            }));
+        });
+    }
+    group.finish();
+}
+
+/// Spawns and then despawns entities with components.
+fn bench_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("churn");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("spawn_despawn", n), &n, |b, &n| {
+            b.iter_batched(|| populate(0), |ecs| {
+                let ecs = ecs.borrow();
+                let entities: Vec<Entity> = (0..n).map(|i| {
+                    let entity: Entity = ecs.add_entity();
+                    ecs.add_component(entity, Position(i as f32, 0.0));
+                    ecs.add_component(entity, Velocity(1.0, 1.0));
+                    entity
+                }).collect();
+                for entity in entities {
+                    ecs.remove_entity(entity);
+                }
+            }, BatchSize::LargeInput);
+        });
+    }
+    group.finish();
+}
+
+/// Records and then applies CommandBuffers.
+fn bench_commands(c: &mut Criterion) {
+    let mut group = c.benchmark_group("commands");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("apply", n), &n, |b, &n| {
+            b.iter_batched(|| {
+                let ecs = populate(0);
+                let mut buffer: CommandBuffer = CommandBuffer::new();
+                {
+                    let reserver: EntityReserver = ecs.borrow().entity_reserver();
+                    for i in 0..n {
+                        let entity: Entity = buffer.spawn(&reserver);
+                        buffer.add_component(entity, Position(i as f32, 0.0));
+                    }
+                }
+                (ecs, buffer)
+            }, |(ecs, buffer)| {
+                ecs.borrow().apply_commands([ buffer ]);
+            }, BatchSize::LargeInput);
+        });
+    }
+    group.finish();
+}
+
+
+
+criterion_group!{
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_iter, bench_join, bench_churn, bench_commands
+}
+criterion_main!(benches);
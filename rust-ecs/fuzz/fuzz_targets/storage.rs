@@ -0,0 +1,85 @@
+//  STORAGE.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:07:49
+//  Last edited:
+//    16 Oct 2026, 00:07:49
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Fuzzes the component storage of the Ecs by applying arbitrary
+//!   sequences of insert/remove/swap/despawn operations, and checking
+//!   that the list mappings stay consistent.
+//!   
+//!   Run with `cargo fuzz run storage` from the `rust-ecs` directory.
+// 
+
+#![no_main]
+
+use std::collections::HashMap;
+
+use libfuzzer_sys::fuzz_target;
+
+use rust_ecs::{Component, Ecs, Entity};
+
+
+/***** COMPONENTS *****/
+/// Simple component to fuzz with.
+#[derive(Debug)]
+struct Value(u8);
+impl Component for Value {}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+fuzz_target!(|data: &[u8]| {
+    let ecs = Ecs::new(4);
+    Ecs::register::<Value>(&ecs);
+    let ecs = ecs.borrow();
+
+    // Spawn a small set of entities so that operations collide often
+    let mut entities: Vec<Entity> = (0..8).map(|_| ecs.add_entity()).collect();
+    let mut model: HashMap<Entity, u8> = HashMap::new();
+
+    // Every operation is an opcode byte followed by an argument byte
+    for op in data.chunks_exact(2) {
+        let entity: Entity = entities[(op[1] & 0x07) as usize];
+        match op[0] % 5 {
+            0 => {
+                if ecs.add_component(entity, Value(op[1])) { model.insert(entity, op[1]); }
+            },
+            1 => {
+                assert_eq!(ecs.remove_component::<Value>(entity).map(|v| v.0), model.remove(&entity));
+            },
+            2 => {
+                let other: Entity = entities[((op[1] >> 3) & 0x07) as usize];
+                ecs.swap_component::<Value>(entity, other);
+                let (a, b) = (model.remove(&entity), model.remove(&other));
+                if let Some(v) = a { model.insert(other, v); }
+                if let Some(v) = b { model.insert(entity, v); }
+            },
+            3 => {
+                // Despawn and immediately spawn a replacement, so the set of entities stays the same size
+                assert!(ecs.remove_entity(entity));
+                model.remove(&entity);
+                entities[(op[1] & 0x07) as usize] = ecs.add_entity();
+            },
+            _ => {
+                ecs.clear_component::<Value>();
+                model.clear();
+            },
+        }
+
+        // Check the list against the model
+        let list = ecs.list_component::<Value>();
+        assert!(list.is_consistent());
+        assert_eq!(list.len(), model.len());
+        for (entity, value) in &model {
+            assert_eq!(list.get(*entity).map(|v| v.0), Some(*value));
+        }
+    }
+});
@@ -0,0 +1,121 @@
+//  WIRE_DECODE.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:32:44
+//  Last edited:
+//    16 Oct 2026, 00:32:44
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Fuzzes the decoding side of the wire format by feeding it arbitrary
+//!   bytes, both as whole component lists and as sequences of single
+//!   values. Decoding must never panic, and whatever decodes successfully
+//!   must survive a re-encode/decode round trip unchanged.
+//!   
+//!   Run with `cargo fuzz run wire_decode` from the `rust-ecs` directory.
+// 
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use rust_ecs::{Component, EcsError, Entity, WireCodec, WireReader, WireWriter};
+use rust_ecs::wire::decode_components;
+
+
+/***** HELPER FUNCTIONS *****/
+/// Decodes a single value of type `T`, and checks that re-encoding it is stable.
+/// 
+/// **Arguments**
+///  * `reader`: The reader to decode the value from.
+/// 
+/// **Returns**  
+/// Whether decoding succeeded.
+fn check<T: WireCodec>(reader: &mut WireReader) -> bool {
+    let value: T = match T::decode(reader) {
+        Ok(value) => value,
+        Err(_)    => { return false; },
+    };
+
+    // Encoding is canonical, so a second round trip must give the same bytes
+    let mut writer: WireWriter = WireWriter::new();
+    value.encode(&mut writer);
+    let bytes: Vec<u8> = writer.into_bytes();
+    let mut reader: WireReader = WireReader::new(&bytes);
+    let again: T = T::decode(&mut reader).expect("Could not decode re-encoded value");
+    assert!(reader.is_empty());
+    let mut writer: WireWriter = WireWriter::new();
+    again.encode(&mut writer);
+    assert_eq!(writer.into_bytes(), bytes);
+    true
+}
+
+
+
+
+
+/***** COMPONENTS *****/
+/// Component that uses (almost) every WireCodec implementation.
+#[derive(Debug)]
+struct Sample {
+    a : u64,
+    b : i32,
+    c : f32,
+    d : bool,
+    e : String,
+    f : Option<Entity>,
+    g : Vec<i16>,
+}
+impl Component for Sample {}
+impl WireCodec for Sample {
+    fn encode(&self, writer: &mut WireWriter) {
+        self.a.encode(writer);
+        self.b.encode(writer);
+        self.c.encode(writer);
+        self.d.encode(writer);
+        self.e.encode(writer);
+        self.f.encode(writer);
+        self.g.encode(writer);
+    }
+    fn decode(reader: &mut WireReader) -> Result<Self, EcsError> {
+        Ok(Self {
+            a : u64::decode(reader)?,
+            b : i32::decode(reader)?,
+            c : f32::decode(reader)?,
+            d : bool::decode(reader)?,
+            e : String::decode(reader)?,
+            f : Option::<Entity>::decode(reader)?,
+            g : Vec::<i16>::decode(reader)?,
+        })
+    }
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+fuzz_target!(|data: &[u8]| {
+    // Decode the data as a whole component list
+    let _ = decode_components::<Sample>(data);
+
+    // Decode the data as a sequence of values, where every value is preceded by a byte that selects its type
+    let mut reader: WireReader = WireReader::new(data);
+    while let Ok(kind) = reader.read_u8() {
+        let ok: bool = match kind % 10 {
+            0 => check::<u8>(&mut reader),
+            1 => check::<u32>(&mut reader),
+            2 => check::<u64>(&mut reader),
+            3 => check::<i64>(&mut reader),
+            4 => check::<f64>(&mut reader),
+            5 => check::<bool>(&mut reader),
+            6 => check::<String>(&mut reader),
+            7 => check::<Entity>(&mut reader),
+            8 => check::<Option<u16>>(&mut reader),
+            _ => check::<Vec<Sample>>(&mut reader),
+        };
+        if !ok { break; }
+    }
+});
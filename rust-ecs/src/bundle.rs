@@ -0,0 +1,114 @@
+//  BUNDLE.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:41:02
+//  Last edited:
+//    16 Oct 2026, 00:50:59
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines the Bundle trait, which allows a (statically known) set of
+//!   Component types to be operated on in one go.
+// 
+
+use std::any::TypeId;
+
+use parking_lot::MappedRwLockWriteGuard;
+
+use crate::spec::{Component, Entity};
+use crate::list::ComponentList;
+use crate::system::Ecs;
+
+
+/***** HELPER MACROS *****/
+/// Implements the Bundle trait for a tuple of the given generic types.
+macro_rules! impl_bundle {
+    ($($t:ident),+) => {
+        impl<$($t: 'static + Component),+> Bundle for ($($t,)+) {
+            type Removed = ($(Option<$t>,)+);
+
+            #[inline]
+            fn remove(ecs: &Ecs, entity: Entity) -> Self::Removed {
+                ($(ecs.remove_component::<$t>(entity),)+)
+            }
+
+            #[allow(non_snake_case)]
+            fn take(ecs: &Ecs, entity: Entity) -> Option<Self> {
+                // Locking the same list twice would deadlock
+                if has_duplicates(&[$(ComponentList::<$t>::id()),+]) { return None; }
+
+                // Lock all lists first
+                $(let mut $t: MappedRwLockWriteGuard<ComponentList<$t>> = ecs.list_component_mut::<$t>();)+
+
+                // Only remove if the entity has all of them
+                $($t.get(entity)?;)+
+                Some(($($t.remove(entity).unwrap(),)+))
+            }
+        }
+    };
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Checks whether the given list of (component list) type IDs contains any duplicates.
+/// 
+/// **Arguments**
+///  * `ids`: The IDs to check.
+/// 
+/// **Returns**  
+/// True if any ID occurs more than once, or false otherwise.
+pub(crate) fn has_duplicates(ids: &[TypeId]) -> bool {
+    ids.iter().enumerate().any(|(i, id)| ids[..i].contains(id))
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines a set of Component types that may be operated on in one go.
+/// 
+/// It is implemented for tuples of up to eight Components. Note that every Component type may only occur once in the tuple; `Bundle::take()` refuses tuples that violate this.
+pub trait Bundle: Sized {
+    /// The result of removing this Bundle from an entity, which tells which Components were present.
+    type Removed;
+
+
+    /// Removes all the components in this Bundle from the given entity.
+    /// 
+    /// Every component list is locked only once.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to remove the components from.
+    ///  * `entity`: The Entity to remove the components of.
+    /// 
+    /// **Returns**  
+    /// A tuple with, for every Component type, the removed component if the entity had it or else None.
+    fn remove(ecs: &Ecs, entity: Entity) -> Self::Removed;
+
+    /// Removes all the components in this Bundle from the given entity, but only if it has all of them.
+    /// 
+    /// Every component list is locked only once.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to remove the components from.
+    ///  * `entity`: The Entity to remove the components of.
+    /// 
+    /// **Returns**  
+    /// The removed components as a Bundle, or None if the entity did not have all of them or if a Component type occurs more than once in the Bundle (in which case nothing is removed).
+    fn take(ecs: &Ecs, entity: Entity) -> Option<Self>;
+}
+
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
@@ -0,0 +1,262 @@
+//  CHECKSUM.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:13:51
+//  Last edited:
+//    16 Oct 2026, 00:14:29
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines deterministic hashes of the state of the Ecs, which can be
+//!   compared between machines (e.g., in lockstep multiplayer) to detect
+//!   desyncs.
+// 
+
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+pub use crate::errors::EcsError as Error;
+use crate::spec::{Component, Entity};
+use crate::system::Ecs;
+use crate::wire::{decode_components, encode_components, WireCodec, WireWriter};
+
+
+/***** UNIT TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wire::WireReader;
+
+    /// Simple component to test with
+    #[derive(Debug, PartialEq)]
+    struct Score(u32);
+    impl Component for Score {}
+    impl WireCodec for Score {
+        fn encode(&self, writer: &mut WireWriter) { self.0.encode(writer); }
+        fn decode(reader: &mut WireReader) -> Result<Self, Error> { Ok(Self(u32::decode(reader)?)) }
+    }
+
+    /// Tests reporting the differences between two encoded component lists
+    #[test]
+    fn test_desync_report() {
+        let (ecs, [ a, b, _ ]) = crate::world!(Score;
+            { Score(1) },
+            { Score(2) },
+            { Score(3) },
+        );
+        let ecs = ecs.borrow();
+        let ours: Vec<u8> = encode_components::<Score>(&ecs);
+        assert!(desync_report::<Score>(&ours, &ours).unwrap().is_empty());
+
+        // Change, remove and add a component
+        ecs.get_component_mut::<Score>(a).unwrap().0 = 10;
+        ecs.remove_component::<Score>(b);
+        let d: Entity = ecs.add_entity();
+        ecs.add_component(d, Score(4));
+        let theirs: Vec<u8> = encode_components::<Score>(&ecs);
+        let name: &str = std::any::type_name::<Score>();
+        assert_eq!(desync_report::<Score>(&ours, &theirs).unwrap(), vec![
+            format!("entity {}: {} is Score(1) locally, but Score(10) remotely", a, name),
+            format!("entity {}: {} only exists locally (Score(2))", b, name),
+            format!("entity {}: {} only exists remotely (Score(4))", d, name),
+        ]);
+
+        // Garbage is refused
+        assert!(desync_report::<Score>(&ours, &[ 0xFF ]).is_err());
+    }
+}
+
+
+/***** HELPER MACROS *****/
+/// Implements the HashedComponents trait for a tuple of the given generic types.
+macro_rules! impl_hashed_components {
+    ($($t:ident),+) => {
+        impl<$($t: 'static + Component + WireCodec),+> HashedComponents for ($($t,)+) {
+            #[inline]
+            fn hashes(ecs: &Ecs) -> Vec<(&'static str, u64)> {
+                vec![$((std::any::type_name::<$t>(), fnv1a(FNV_OFFSET, &encode_components::<$t>(ecs))),)+]
+            }
+        }
+    };
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The offset basis of the 64-bit FNV-1a hash.
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+/// The prime of the 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x100000001b3;
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Continues a 64-bit FNV-1a hash with the given bytes.
+/// 
+/// We use this instead of the standard library's hasher, because that one is not guaranteed to be the same between Rust versions (or machines).
+/// 
+/// **Arguments**
+///  * `hash`: The hash so far (or `FNV_OFFSET` to start a new one).
+///  * `bytes`: The bytes to hash.
+/// 
+/// **Returns**  
+/// The new hash.
+#[inline]
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+
+
+
+
+/***** LIBRARY FUNCTIONS *****/
+/// Reports which components differ between two encoded component lists (as produced by `wire::encode_components()`).
+/// 
+/// This is meant for diagnosing desyncs: once two StateHashes diverge, both sides exchange the encoding of the diverging component types and compare them here.
+/// 
+/// **Generic Types**
+///  * `T`: The Component type that is encoded in both lists.
+/// 
+/// **Arguments**
+///  * `ours`: The encoded components on this side.
+///  * `theirs`: The encoded components on the remote side.
+/// 
+/// **Returns**  
+/// One human-readable line per differing entity, in order of entity, or an empty list if the lists are the same.
+/// 
+/// **Errors**  
+/// This function errors if either list cannot be decoded.
+pub fn desync_report<T: WireCodec + PartialEq + Debug>(ours: &[u8], theirs: &[u8]) -> Result<Vec<String>, Error> {
+    let name: &'static str = std::any::type_name::<T>();
+    let mut ours   = decode_components::<T>(ours)?.into_iter().peekable();
+    let mut theirs = decode_components::<T>(theirs)?.into_iter().peekable();
+
+    // Walk both (sorted) lists in lockstep
+    let mut result: Vec<String> = vec![];
+    loop {
+        let order: Option<Ordering> = match (ours.peek(), theirs.peek()) {
+            (Some((a, _)), Some((b, _))) => Some(a.cmp(b)),
+            (Some(_), None)              => Some(Ordering::Less),
+            (None, Some(_))              => Some(Ordering::Greater),
+            (None, None)                 => None,
+        };
+        match order {
+            Some(Ordering::Less) => {
+                let (entity, value) = ours.next().unwrap();
+                result.push(format!("entity {}: {} only exists locally ({:?})", entity, name, value));
+            },
+            Some(Ordering::Greater) => {
+                let (entity, value) = theirs.next().unwrap();
+                result.push(format!("entity {}: {} only exists remotely ({:?})", entity, name, value));
+            },
+            Some(Ordering::Equal) => {
+                let ((entity, a), (_, b)) = (ours.next().unwrap(), theirs.next().unwrap());
+                if a != b { result.push(format!("entity {}: {} is {:?} locally, but {:?} remotely", entity, name, a, b)); }
+            },
+            None => { break; },
+        }
+    }
+    Ok(result)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines a set of Component types that are included in a StateHash.
+/// 
+/// It is implemented for tuples of up to eight Components that implement `WireCodec`, whose encoding is what gets hashed.
+pub trait HashedComponents {
+    /// Hashes the components of every type in the set.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to hash the components of.
+    /// 
+    /// **Returns**  
+    /// The name and hash of every type, in order.
+    fn hashes(ecs: &Ecs) -> Vec<(&'static str, u64)>;
+}
+
+impl_hashed_components!(A);
+impl_hashed_components!(A, B);
+impl_hashed_components!(A, B, C);
+impl_hashed_components!(A, B, C, D);
+impl_hashed_components!(A, B, C, D, E);
+impl_hashed_components!(A, B, C, D, E, F);
+impl_hashed_components!(A, B, C, D, E, F, G);
+impl_hashed_components!(A, B, C, D, E, F, G, H);
+
+
+
+/// A deterministic hash of the state of an Ecs, as computed by `Ecs::state_hash()`.
+/// 
+/// The hashes only depend on the entity IDs and the wire encoding of the components, so they are the same on every machine that has the same state. When the totals differ, the sub-hashes tell which part of the state diverged.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct StateHash {
+    /// The hash of everything below.
+    pub total      : u64,
+    /// The hash of the set of alive entities.
+    pub entities   : u64,
+    /// The name and hash of every hashed component type.
+    pub components : Vec<(&'static str, u64)>,
+}
+
+impl StateHash {
+    /// Computes the StateHash of the given Ecs.
+    /// 
+    /// **Generic Types**
+    ///  * `H`: The tuple of Component types to hash.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to hash.
+    pub(crate) fn compute<H: HashedComponents>(ecs: &Ecs) -> Self {
+        // Hash the entities in order
+        let mut entities: Vec<Entity> = ecs.entities();
+        entities.sort();
+        let mut writer: WireWriter = WireWriter::new();
+        for entity in entities {
+            entity.encode(&mut writer);
+        }
+        let entities: u64 = fnv1a(FNV_OFFSET, &writer.into_bytes());
+
+        // Hash the components, then combine everything
+        let components: Vec<(&'static str, u64)> = H::hashes(ecs);
+        let total: u64 = components.iter().fold(fnv1a(FNV_OFFSET, &entities.to_le_bytes()), |total, (_, hash)| fnv1a(total, &hash.to_le_bytes()));
+        Self {
+            total,
+            entities,
+            components,
+        }
+    }
+
+
+
+    /// Returns which parts of the state differ from another StateHash.
+    /// 
+    /// **Arguments**
+    ///  * `other`: The StateHash to compare with. Should have been computed with the same component types.
+    /// 
+    /// **Returns**  
+    /// The names of the component types whose hashes differ, plus `"entities"` if the set of entities differs. Empty if the states are the same.
+    pub fn diverging(&self, other: &StateHash) -> Vec<&'static str> {
+        let mut result: Vec<&'static str> = vec![];
+        if self.entities != other.entities { result.push("entities"); }
+        for (name, hash) in &self.components {
+            if other.components.iter().find(|(other_name, _)| other_name == name).map(|(_, other_hash)| other_hash != hash).unwrap_or(true) {
+                result.push(name);
+            }
+        }
+        for (name, _) in &other.components {
+            if !self.components.iter().any(|(self_name, _)| self_name == name) { result.push(name); }
+        }
+        result
+    }
+}
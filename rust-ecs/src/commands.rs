@@ -0,0 +1,139 @@
+//  COMMANDS.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:47:11
+//  Last edited:
+//    16 Oct 2026, 00:48:07
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines CommandBuffers, which record structural changes to the Ecs
+//!   so they may be applied later (e.g., at a sync point after parallel
+//!   systems have run).
+// 
+
+use crate::spec::{Component, Entity, EntityReserver};
+use crate::system::Ecs;
+
+
+/***** AUXILLARY *****/
+/// A single, type-erased command in a CommandBuffer.
+type Command = Box<dyn FnOnce(&Ecs) + Send>;
+
+
+
+
+
+/***** LIBRARY *****/
+/// Records changes to the Ecs so that they can be applied later using `Ecs::apply_commands()`.
+/// 
+/// Every (parallel) system should get its own CommandBuffer, so no locking is needed while recording. The buffers are then applied in a defined order at the next sync point.
+#[derive(Default)]
+pub struct CommandBuffer {
+    /// The commands recorded so far, in order.
+    commands : Vec<Command>,
+}
+
+impl CommandBuffer {
+    /// Constructor for the CommandBuffer.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            commands : vec![],
+        }
+    }
+
+
+
+    /// Spawns a new entity.
+    /// 
+    /// The entity ID is reserved immediately, so it can be used in subsequent commands. It becomes alive when this command is applied, and never if the buffer is dropped without being applied (the ID is not handed out again either way).
+    /// 
+    /// **Arguments**
+    ///  * `reserver`: The EntityReserver of the Ecs to spawn the entity in (see `Ecs::entity_reserver()`). Unlike the Ecs, it may be used on worker threads.
+    /// 
+    /// **Returns**  
+    /// The identifier of the new entity.
+    #[inline]
+    pub fn spawn(&mut self, reserver: &EntityReserver) -> Entity {
+        let entity: Entity = reserver.reserve_deferred();
+        self.push(move |ecs: &Ecs| { ecs.spawn_deferred(entity); });
+        entity
+    }
+
+    /// Removes the given entity.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to remove.
+    #[inline]
+    pub fn despawn(&mut self, entity: Entity) {
+        self.push(move |ecs: &Ecs| { ecs.remove_entity(entity); });
+    }
+
+    /// Adds the given component to the given entity, overwriting any existing one.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to add.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to add the component to.
+    ///  * `data`: The data to set the component value to.
+    #[inline]
+    pub fn add_component<T: 'static + Component + Send>(&mut self, entity: Entity, data: T) {
+        self.push(move |ecs: &Ecs| { ecs.add_component(entity, data); });
+    }
+
+    /// Removes a component from the given entity.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to remove.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to remove the component of.
+    #[inline]
+    pub fn remove_component<T: 'static + Component>(&mut self, entity: Entity) {
+        self.push(move |ecs: &Ecs| { ecs.remove_component::<T>(entity); });
+    }
+
+    /// Records an arbitrary command.
+    /// 
+    /// **Arguments**
+    ///  * `command`: The command to record, as a closure that is given the Ecs when the buffer is applied.
+    #[inline]
+    pub fn push(&mut self, command: impl 'static + FnOnce(&Ecs) + Send) {
+        self.commands.push(Box::new(command));
+    }
+
+    /// Moves all commands of the given buffer to the end of this one, leaving the other empty.
+    /// 
+    /// **Arguments**
+    ///  * `other`: The CommandBuffer to merge into this one.
+    #[inline]
+    pub fn append(&mut self, other: &mut CommandBuffer) {
+        self.commands.append(&mut other.commands);
+    }
+
+
+
+    /// Applies all commands in this buffer to the given Ecs, in order.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to apply the commands to.
+    pub(crate) fn apply(self, ecs: &Ecs) {
+        for command in self.commands {
+            command(ecs);
+        }
+    }
+
+
+
+    /// Returns the number of commands in this buffer.
+    #[inline]
+    pub fn len(&self) -> usize { self.commands.len() }
+
+    /// Returns whether this buffer has any commands.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.commands.is_empty() }
+}
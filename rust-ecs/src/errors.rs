@@ -0,0 +1,90 @@
+//  ERRORS.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:40:12
+//  Last edited:
+//    16 Oct 2026, 00:31:51
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines errors for the `rust-ecs` crate.
+// 
+
+use std::any::TypeId;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::num::ParseIntError;
+
+use crate::spec::Entity;
+
+
+/***** LIBRARY *****/
+/// Defines the toplevel errors that occur in the crate.
+#[derive(Debug)]
+pub enum EcsError {
+    /// Could not acquire a lock without blocking.
+    WouldBlock{ what: &'static str },
+    /// Attempted to create an Entity with ID zero.
+    NullEntity,
+    /// Failed to parse an Entity from a string.
+    EntityParseError{ raw: String, err: ParseIntError },
+    /// The given component type has not been registered.
+    UnregisteredComponent{ id: TypeId },
+    /// The same component type was requested more than once in a split borrow.
+    DuplicateAccess{ name: &'static str },
+    /// Spawning or adding would exceed the hard limit of a quota.
+    QuotaExceeded{ what: &'static str, limit: usize },
+    /// An entity could not be spawned with the given ID, because that ID has already been used (or can never be handed out).
+    UnavailableEntityId{ entity: Entity },
+    /// A component could not be added to an entity (e.g., because its fixed-capacity list is full).
+    ComponentRejected{ name: &'static str, entity: Entity },
+    /// Attempted to extract entities from an Ecs into itself.
+    ExtractIntoSelf,
+    /// A component failed validation (see `Validate`).
+    InvalidComponent{ name: &'static str, entity: Entity, reason: String },
+
+    /// The wire data ended before a value was completely decoded.
+    WireUnexpectedEnd,
+    /// A varint in the wire data does not fit in 64 bits.
+    WireVarintOverflow,
+    /// The wire data contains a value that is not valid for its type.
+    WireInvalidValue{ what: &'static str },
+    /// The wire data was encoded with an unsupported version of the format.
+    WireUnsupportedVersion{ got: u8, expected: u8 },
+
+    /// The given one-shot system does not exist.
+    UnknownSystem{ id: u64 },
+    /// The given one-shot system is already running (i.e., it tried to run itself).
+    SystemAlreadyRunning{ id: u64 },
+}
+
+impl Display for EcsError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use EcsError::*;
+        match self {
+            WouldBlock{ what }                       => write!(f, "Could not lock {} without blocking", what),
+            NullEntity                               => write!(f, "Entity IDs cannot be zero"),
+            EntityParseError{ raw, err }             => write!(f, "Could not parse '{}' as an Entity: {}", raw, err),
+            UnregisteredComponent{ id }              => write!(f, "Unregistered Component type '{:?}'", id),
+            DuplicateAccess{ name }                  => write!(f, "Component type '{}' is accessed more than once", name),
+            QuotaExceeded{ what, limit }             => write!(f, "Quota of {} {} exceeded", limit, what),
+            UnavailableEntityId{ entity }            => write!(f, "Cannot spawn entity {}, as its ID has already been used", entity),
+            ComponentRejected{ name, entity }        => write!(f, "Could not add component '{}' to entity {}", name, entity),
+            ExtractIntoSelf                          => write!(f, "Cannot extract entities from an Ecs into itself"),
+            InvalidComponent{ name, entity, reason } => write!(f, "Invalid component '{}' of entity {}: {}", name, entity, reason),
+
+            WireUnexpectedEnd                       => write!(f, "Unexpected end of wire data"),
+            WireVarintOverflow                      => write!(f, "Varint in wire data does not fit in 64 bits"),
+            WireInvalidValue{ what }                => write!(f, "Invalid {} in wire data", what),
+            WireUnsupportedVersion{ got, expected } => write!(f, "Unsupported wire format version {} (expected {})", got, expected),
+
+            UnknownSystem{ id }        => write!(f, "Unknown system {}", id),
+            SystemAlreadyRunning{ id } => write!(f, "System {} is already running", id),
+        }
+    }
+}
+
+impl Error for EcsError {}
@@ -0,0 +1,265 @@
+//  EVENTS.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:47:45
+//  Last edited:
+//    15 Oct 2026, 23:47:45
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines event queues with configurable retention, and readers that
+//!   keep track of which events they have already seen.
+// 
+
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+
+/***** UNIT TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the default (double-buffered) retention
+    #[test]
+    fn test_double_buffered() {
+        let mut events: Events<u32> = Events::new();
+        let mut reader: ManualEventReader<u32> = events.get_reader();
+
+        // Events survive one update, but not two
+        events.send(1);
+        events.update();
+        events.send(2);
+        assert_eq!(reader.read(&events).copied().collect::<Vec<u32>>(), vec![ 1, 2 ]);
+        assert_eq!(reader.read(&events).count(), 0);
+        events.update();
+        assert_eq!(events.len(), 1);
+        events.update();
+        assert!(events.is_empty());
+
+        // A late reader misses the dropped events
+        let mut late: ManualEventReader<u32> = ManualEventReader::default();
+        events.send(3);
+        assert_eq!(late.missed(&events), 2);
+        assert_eq!(late.read(&events).copied().collect::<Vec<u32>>(), vec![ 3 ]);
+    }
+
+    /// Tests the manual and ring buffer retentions
+    #[test]
+    fn test_manual_ring() {
+        let mut events: Events<u32> = Events::with_retention(EventRetention::Manual);
+        for i in 0..4 { events.send(i); events.update(); }
+        assert_eq!(events.len(), 4);
+        events.clear();
+        assert!(events.is_empty());
+
+        let mut events: Events<u32> = Events::with_retention(EventRetention::RingBuffer(2));
+        let mut reader: ManualEventReader<u32> = events.get_reader();
+        for i in 0..4 { events.send(i); }
+        assert_eq!(reader.missed(&events), 2);
+        assert_eq!(reader.read(&events).copied().collect::<Vec<u32>>(), vec![ 2, 3 ]);
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines how long an event queue keeps its events around.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EventRetention {
+    /// Events are dropped at the second call to `Events::update()` after they have been sent. This gives every reader that runs once per update the chance to see them.
+    DoubleBuffered,
+    /// Events are kept until `Events::clear()` is called.
+    Manual,
+    /// Events are kept until more than the given number of events are in the queue, after which the oldest ones are dropped.
+    RingBuffer(usize),
+}
+
+impl Default for EventRetention {
+    #[inline]
+    fn default() -> Self { EventRetention::DoubleBuffered }
+}
+
+
+
+/// A queue of events of a particular type.
+/// 
+/// Every event gets a sequential ID, which is used by readers to track which events they have already seen.
+/// 
+/// **Generic Types**
+///  * `E`: The type of the events in this queue.
+pub struct Events<E> {
+    /// The events in the queue, oldest first.
+    events    : VecDeque<E>,
+    /// The ID of the oldest event in the queue.
+    start_id  : u64,
+    /// The ID of the first event sent after the previous call to `Events::update()`.
+    update_id : u64,
+    /// How long we keep events around.
+    retention : EventRetention,
+}
+
+impl<E> Events<E> {
+    /// Constructor for the Events that uses the default (double-buffered) retention.
+    #[inline]
+    pub fn new() -> Self { Self::with_retention(EventRetention::default()) }
+
+    /// Constructor for the Events with a custom retention.
+    /// 
+    /// **Arguments**
+    ///  * `retention`: Decides how long events are kept around.
+    #[inline]
+    pub fn with_retention(retention: EventRetention) -> Self {
+        Self {
+            events    : VecDeque::new(),
+            start_id  : 0,
+            update_id : 0,
+            retention,
+        }
+    }
+
+
+
+    /// Sends a new event.
+    /// 
+    /// **Arguments**
+    ///  * `event`: The event to send.
+    pub fn send(&mut self, event: E) {
+        self.events.push_back(event);
+
+        // Drop the oldest event if the ring buffer is full
+        if let EventRetention::RingBuffer(capacity) = self.retention {
+            while self.events.len() > capacity {
+                self.events.pop_front();
+                self.start_id += 1;
+            }
+        }
+    }
+
+    /// Updates the queue, which should be called once per frame.
+    /// 
+    /// For double-buffered queues, this drops all events sent before the previous update. For the other retentions, this does nothing.
+    pub fn update(&mut self) {
+        let end_id: u64 = self.end_id();
+        if self.retention == EventRetention::DoubleBuffered {
+            while self.start_id < self.update_id {
+                self.events.pop_front();
+                self.start_id += 1;
+            }
+        }
+        self.update_id = end_id;
+    }
+
+    /// Drops all events in the queue.
+    pub fn clear(&mut self) {
+        self.start_id = self.end_id();
+        self.update_id = self.start_id;
+        self.events.clear();
+    }
+
+
+
+    /// Returns a new reader that will only see events sent after this call.
+    #[inline]
+    pub fn get_reader(&self) -> ManualEventReader<E> {
+        ManualEventReader {
+            next_id : self.end_id(),
+            _event  : PhantomData,
+        }
+    }
+
+    /// Returns an iterator over all events currently in the queue, oldest first.
+    #[inline]
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, E> { self.events.iter() }
+
+    /// Returns the ID that the next sent event will get.
+    #[inline]
+    fn end_id(&self) -> u64 { self.start_id + self.events.len() as u64 }
+
+    /// Returns the retention strategy of this queue.
+    #[inline]
+    pub fn retention(&self) -> EventRetention { self.retention }
+
+    /// Returns the number of events currently in the queue.
+    #[inline]
+    pub fn len(&self) -> usize { self.events.len() }
+
+    /// Returns whether there are any events currently in the queue.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.events.is_empty() }
+}
+
+impl<E> Default for Events<E> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+
+
+/// Reads events from an Events queue, keeping track of which events it has already seen.
+/// 
+/// Because it does not borrow the queue, it can be stored by consumers that live outside of the normal flow of the game (e.g., a network send loop).
+/// 
+/// **Generic Types**
+///  * `E`: The type of the events this reader reads.
+pub struct ManualEventReader<E> {
+    /// The ID of the next event to read.
+    next_id : u64,
+    /// Binds the event type to this reader.
+    _event  : PhantomData<fn() -> E>,
+}
+
+impl<E> ManualEventReader<E> {
+    /// Returns all events in the given queue that this reader has not yet seen, and marks them as seen.
+    /// 
+    /// **Arguments**
+    ///  * `events`: The queue to read from.
+    /// 
+    /// **Returns**  
+    /// An iterator over the unseen events, oldest first.
+    pub fn read<'a>(&mut self, events: &'a Events<E>) -> impl Iterator<Item = &'a E> {
+        let skip: usize = self.next_id.saturating_sub(events.start_id) as usize;
+        self.next_id = events.end_id();
+        events.events.iter().skip(skip)
+    }
+
+    /// Returns the number of events in the given queue that this reader has not yet seen.
+    /// 
+    /// **Arguments**
+    ///  * `events`: The queue to check.
+    #[inline]
+    pub fn len(&self, events: &Events<E>) -> usize {
+        events.end_id().saturating_sub(self.next_id.max(events.start_id)) as usize
+    }
+
+    /// Returns whether the given queue has any events that this reader has not yet seen.
+    /// 
+    /// **Arguments**
+    ///  * `events`: The queue to check.
+    #[inline]
+    pub fn is_empty(&self, events: &Events<E>) -> bool { self.len(events) == 0 }
+
+    /// Returns the number of events that have been dropped from the given queue before this reader could see them.
+    /// 
+    /// **Arguments**
+    ///  * `events`: The queue to check.
+    #[inline]
+    pub fn missed(&self, events: &Events<E>) -> usize {
+        events.start_id.saturating_sub(self.next_id) as usize
+    }
+}
+
+impl<E> Default for ManualEventReader<E> {
+    /// Creates a reader that will see all events currently in any queue.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            next_id : 0,
+            _event  : PhantomData,
+        }
+    }
+}
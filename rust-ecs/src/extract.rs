@@ -0,0 +1,107 @@
+//  EXTRACT.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:13:04
+//  Last edited:
+//    16 Oct 2026, 00:29:40
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines the types used by `Ecs::extract_filtered()`, which copies
+//!   selected entities and component types into another Ecs.
+// 
+
+use std::any::TypeId;
+
+use parking_lot::MappedRwLockReadGuard;
+
+use crate::errors::EcsError;
+use crate::spec::{Component, Entity, EntityHashMap};
+use crate::list::ComponentList;
+use crate::system::Ecs;
+
+
+/***** HELPER MACROS *****/
+/// Implements the Extract trait for a tuple of the given generic types.
+macro_rules! impl_extract {
+    ($($t:ident),+) => {
+        impl<$($t: 'static + Component + Clone),+> Extract for ($($t,)+) {
+            #[inline]
+            fn unregistered(target: &Ecs) -> Option<TypeId> {
+                $(if !target.is_registered::<$t>() { return Some(TypeId::of::<$t>()); })+
+                None
+            }
+
+            fn copy(source: &Ecs, target: &Ecs, mapping: &[(Entity, Entity)]) -> Result<(), EcsError> {
+                $({
+                    let from: MappedRwLockReadGuard<ComponentList<$t>> = source.list_component::<$t>();
+                    for (src, dst) in mapping {
+                        match from.get(*src) {
+                            Some(component) => if !target.add_component(*dst, component.clone()) {
+                                return Err(EcsError::ComponentRejected{ name: std::any::type_name::<$t>(), entity: *dst });
+                            },
+                            None => { target.remove_component::<$t>(*dst); },
+                        }
+                    }
+                })+
+                Ok(())
+            }
+        }
+    };
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines how `Ecs::extract_filtered()` assigns entities in the target Ecs.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ExtractIds {
+    /// Every extracted entity keeps its ID. Entities that are already alive in the target are reused (and their extracted components overwritten), so extracting into the same target every frame keeps it in sync.
+    Preserve,
+    /// Every extracted entity is spawned as a new entity in the target.
+    Remap,
+}
+
+
+
+/// Defines a set of (cloneable) Component types that may be copied by `Ecs::extract_filtered()`.
+/// 
+/// It is implemented for tuples of up to eight Components. Unlike a Snapshot, entities do not need to have all of the types; every type is copied on its own.
+pub trait Extract {
+    /// Returns the first Component type in the set that is not registered in the given Ecs, if any.
+    /// 
+    /// **Arguments**
+    ///  * `target`: The Ecs to check.
+    fn unregistered(target: &Ecs) -> Option<TypeId>;
+
+    /// Copies the components of the given entities from one Ecs to another.
+    /// 
+    /// Target entities whose source entity does not have a component will have it removed, so the target matches the source afterwards. Components are added through the target Ecs as usual, so its quotas, validators and journal apply.
+    /// 
+    /// **Arguments**
+    ///  * `source`: The Ecs to copy the components from.
+    ///  * `target`: The Ecs to copy the components to. Must be a different Ecs than `source`, and all Component types must be registered in it.
+    ///  * `mapping`: The (source, target) entity pairs to copy.
+    /// 
+    /// **Errors**  
+    /// This function errors if the target refuses a component (see `Ecs::add_component()`). The components copied before that remain in the target.
+    fn copy(source: &Ecs, target: &Ecs, mapping: &[(Entity, Entity)]) -> Result<(), EcsError>;
+}
+
+impl_extract!(A);
+impl_extract!(A, B);
+impl_extract!(A, B, C);
+impl_extract!(A, B, C, D);
+impl_extract!(A, B, C, D, E);
+impl_extract!(A, B, C, D, E, F);
+impl_extract!(A, B, C, D, E, F, G);
+impl_extract!(A, B, C, D, E, F, G, H);
+
+
+
+/// Maps the entities of one Ecs to the entities in another, as returned by `Ecs::extract_filtered()`.
+pub type EntityMapping = EntityHashMap<Entity>;
@@ -0,0 +1,138 @@
+//  FSM.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:00:02
+//  Last edited:
+//    16 Oct 2026, 00:00:02
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines generic finite state machine components, whose transitions
+//!   are evaluated by the Ecs and reported as events.
+// 
+
+use crate::spec::{Component, Entity};
+use crate::system::Ecs;
+
+
+/***** AUXILLARY *****/
+/// A condition that decides whether a transition should be taken for the given entity.
+type Condition = Box<dyn Fn(&Ecs, Entity) -> bool>;
+
+
+
+
+
+/***** LIBRARY *****/
+/// A component that gives an entity a state of type `S`.
+/// 
+/// Use `Ecs::register_state_machine()` to enable it, `Ecs::add_transition()` to define when the state changes, and `Ecs::update_state_machines()` to evaluate them.
+/// 
+/// **Generic Types**
+///  * `S`: The type of the state (typically some enum).
+#[derive(Clone, Debug)]
+pub struct StateMachine<S> {
+    /// The current state.
+    state : S,
+}
+
+impl<S> StateMachine<S> {
+    /// Constructor for the StateMachine.
+    /// 
+    /// **Arguments**
+    ///  * `initial`: The state to start in.
+    #[inline]
+    pub fn new(initial: S) -> Self {
+        Self {
+            state : initial,
+        }
+    }
+
+
+
+    /// Returns the current state.
+    #[inline]
+    pub fn state(&self) -> &S { &self.state }
+}
+
+impl<S> Component for StateMachine<S> {}
+
+
+
+/// The event that is sent whenever a StateMachine changes state.
+/// 
+/// **Generic Types**
+///  * `S`: The type of the state.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateChanged<S> {
+    /// The entity whose state changed.
+    pub entity : Entity,
+    /// The state it was in.
+    pub from   : S,
+    /// The state it is in now.
+    pub to     : S,
+}
+
+
+
+/// Stores the transitions of all StateMachines of a particular state type, as a resource in the Ecs.
+/// 
+/// **Generic Types**
+///  * `S`: The type of the state.
+pub struct Transitions<S> {
+    /// The transitions, in the order in which they are evaluated.
+    transitions : Vec<(S, S, Condition)>,
+}
+
+impl<S: PartialEq> Transitions<S> {
+    /// Constructor for the Transitions, which initially has none.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            transitions : vec![],
+        }
+    }
+
+
+
+    /// Adds a new transition.
+    /// 
+    /// **Arguments**
+    ///  * `from`: The state in which the transition may be taken.
+    ///  * `to`: The state the transition leads to.
+    ///  * `condition`: Decides, for a given entity, whether the transition should be taken.
+    #[inline]
+    pub fn add(&mut self, from: S, to: S, condition: impl 'static + Fn(&Ecs, Entity) -> bool) {
+        self.transitions.push((from, to, Box::new(condition)));
+    }
+
+    /// Finds the first transition that should be taken by the given entity.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to pass to the conditions.
+    ///  * `entity`: The entity to evaluate the transitions for.
+    ///  * `state`: The current state of that entity.
+    /// 
+    /// **Returns**  
+    /// The state to transition to, or None if no transition should be taken.
+    pub(crate) fn next(&self, ecs: &Ecs, entity: Entity, state: &S) -> Option<&S> {
+        self.transitions.iter()
+            .find(|(from, _, condition)| from == state && condition(ecs, entity))
+            .map(|(_, to, _)| to)
+    }
+
+    /// Returns the number of transitions.
+    #[inline]
+    pub fn len(&self) -> usize { self.transitions.len() }
+
+    /// Returns whether there are any transitions.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.transitions.is_empty() }
+}
+
+impl<S: PartialEq> Default for Transitions<S> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
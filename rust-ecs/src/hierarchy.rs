@@ -0,0 +1,163 @@
+//  HIERARCHY.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:44:45
+//  Last edited:
+//    16 Oct 2026, 00:46:41
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines a parent/child hierarchy between entities (based on
+//!   relations), and iterators to traverse it.
+// 
+
+use std::collections::VecDeque;
+
+use parking_lot::MappedRwLockReadGuard;
+
+use crate::spec::{Entity, EntityHashMap, EntityHashSet};
+use crate::list::ComponentList;
+use crate::relation::Relation;
+
+
+/***** LIBRARY *****/
+/// The kind of relation that links a child entity to its parent.
+/// 
+/// A hierarchy is simply a set of `Relation<ChildOf>` components; use `Ecs::register_hierarchy()` to enable it and `Ecs::set_parent()` to build it.
+pub struct ChildOf;
+
+
+
+/// Defines the order in which descendants are traversed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TraversalOrder {
+    /// Visits all of an entity's descendants before moving to its next sibling.
+    DepthFirst,
+    /// Visits all entities at the same depth before moving to the next depth.
+    BreadthFirst,
+}
+
+
+
+/// Iterates over the ancestors of an entity, from its parent up to the root of the hierarchy.
+/// 
+/// The list of hierarchy relations is locked for as long as the iterator lives. If the hierarchy contains a cycle (which can only happen by bypassing the Ecs), iteration stops before visiting any entity twice.
+pub struct Ancestors<'a> {
+    /// The (locked) list of hierarchy relations.
+    list    : MappedRwLockReadGuard<'a, ComponentList<Relation<ChildOf>>>,
+    /// The entity of which we will return the parent next.
+    current : Entity,
+    /// The entities we have already visited (including the one we started at).
+    visited : EntityHashSet,
+}
+
+impl<'a> Ancestors<'a> {
+    /// Constructor for the Ancestors iterator.
+    /// 
+    /// **Arguments**
+    ///  * `list`: The (locked) list of hierarchy relations.
+    ///  * `entity`: The entity of which to iterate the ancestors.
+    #[inline]
+    pub(crate) fn new(list: MappedRwLockReadGuard<'a, ComponentList<Relation<ChildOf>>>, entity: Entity) -> Self {
+        let mut visited: EntityHashSet = EntityHashSet::default();
+        visited.insert(entity);
+        Self {
+            list,
+            current : entity,
+            visited,
+        }
+    }
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let parent: Entity = self.list.get(self.current)?.target();
+        // Stop at cycles
+        if !self.visited.insert(parent) { return None; }
+        self.current = parent;
+        Some(parent)
+    }
+}
+
+
+
+/// Iterates over the descendants of an entity, either depth-first or breadth-first.
+/// 
+/// The hierarchy is snapshotted when the iterator is created, so no locks are held while iterating. Children of the same parent are visited in order of their entity ID, and every entity is visited at most once (even if the hierarchy contains a cycle).
+pub struct Descendants {
+    /// Maps every parent to its children.
+    children : EntityHashMap<Vec<Entity>>,
+    /// The entities we have already visited (including the one we started at).
+    visited  : EntityHashSet,
+    /// The entities we still have to visit.
+    todo     : VecDeque<Entity>,
+    /// The order in which to visit them.
+    order    : TraversalOrder,
+}
+
+impl Descendants {
+    /// Constructor for the Descendants iterator.
+    /// 
+    /// **Arguments**
+    ///  * `list`: The list of hierarchy relations.
+    ///  * `entity`: The entity of which to iterate the descendants.
+    ///  * `order`: The order in which to iterate them.
+    pub(crate) fn new(list: &ComponentList<Relation<ChildOf>>, entity: Entity, order: TraversalOrder) -> Self {
+        // Build a map of parents to children
+        let mut children: EntityHashMap<Vec<Entity>> = EntityHashMap::default();
+        for (child, relation) in list.iter_entities() {
+            children.entry(relation.target()).or_default().push(child);
+        }
+        for list in children.values_mut() {
+            list.sort();
+        }
+
+        // Start with the root's children
+        let mut result = Self {
+            children,
+            visited : EntityHashSet::default(),
+            todo    : VecDeque::new(),
+            order,
+        };
+        result.visited.insert(entity);
+        result.push_children(entity);
+        result
+    }
+
+
+
+    /// Adds the children of the given entity to the list of entities to visit.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The entity of which to add the children.
+    fn push_children(&mut self, entity: Entity) {
+        if let Some(children) = self.children.get(&entity) {
+            match self.order {
+                // Push in reverse so that we pop them in order
+                TraversalOrder::DepthFirst   => { self.todo.extend(children.iter().rev()); },
+                TraversalOrder::BreadthFirst => { self.todo.extend(children.iter()); },
+            }
+        }
+    }
+}
+
+impl Iterator for Descendants {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next: Entity = match self.order {
+                TraversalOrder::DepthFirst   => self.todo.pop_back()?,
+                TraversalOrder::BreadthFirst => self.todo.pop_front()?,
+            };
+            // Skip entities we have already seen (i.e., cycles)
+            if !self.visited.insert(next) { continue; }
+            self.push_children(next);
+            return Some(next);
+        }
+    }
+}
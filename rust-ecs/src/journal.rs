@@ -0,0 +1,152 @@
+//  JOURNAL.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:20:30
+//  Last edited:
+//    16 Oct 2026, 00:30:42
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines the structural journal, which optionally records every
+//!   spawn, despawn, insert and remove of the last few frames so they can
+//!   be inspected (e.g., from a debug console).
+// 
+
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use crate::spec::Entity;
+
+
+/***** LIBRARY *****/
+/// Defines the kinds of structural operations recorded in the Journal.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StructuralOp {
+    /// The entity was spawned (or flushed, if it was reserved).
+    Spawn,
+    /// The entity was despawned.
+    Despawn,
+    /// A component (of the type with the given name) was added to the entity. Overwriting an existing component is not recorded.
+    Insert(&'static str),
+    /// A component (of the type with the given name) was removed from the entity. Components removed because their entity was despawned are not recorded separately, but relations removed because their target was despawned and orphans removed by `Ecs::clean_orphans()` are.
+    Remove(&'static str),
+}
+
+impl Display for StructuralOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use StructuralOp::*;
+        match self {
+            Spawn        => write!(f, "spawn"),
+            Despawn      => write!(f, "despawn"),
+            Insert(name) => write!(f, "insert {}", name),
+            Remove(name) => write!(f, "remove {}", name),
+        }
+    }
+}
+
+
+
+/// A single structural operation recorded in the Journal.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct StructuralChange {
+    /// The change tick (i.e., frame) during which the operation happened.
+    pub tick   : u64,
+    /// The system that performed the operation, if it has been attributed (see `Ecs::attribute()`).
+    pub system : Option<&'static str>,
+    /// The entity that was operated on.
+    pub entity : Entity,
+    /// The operation itself.
+    pub op     : StructuralOp,
+}
+
+impl Display for StructuralChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        write!(f, "[tick {}] {}: {} entity {}", self.tick, self.system.unwrap_or("<unknown>"), self.op, self.entity)
+    }
+}
+
+
+
+/// Records the structural operations of the last few frames, see `Ecs::enable_journal()`.
+#[derive(Clone, Debug)]
+pub struct Journal {
+    /// The recorded changes, oldest first.
+    changes : VecDeque<StructuralChange>,
+    /// The number of frames (change ticks) to keep.
+    frames  : u64,
+    /// The system to which new changes are attributed, if any.
+    system  : Option<&'static str>,
+}
+
+impl Journal {
+    /// Constructor for the Journal.
+    /// 
+    /// **Arguments**
+    ///  * `frames`: The number of frames (change ticks) to keep changes of.
+    #[inline]
+    pub(crate) fn new(frames: u64) -> Self {
+        Self {
+            changes : VecDeque::new(),
+            frames,
+            system  : None,
+        }
+    }
+
+
+
+    /// Records a new structural change, dropping the changes that have become too old.
+    /// 
+    /// **Arguments**
+    ///  * `tick`: The current change tick of the Ecs.
+    ///  * `entity`: The entity that was operated on.
+    ///  * `op`: The operation itself.
+    pub(crate) fn record(&mut self, tick: u64, entity: Entity, op: StructuralOp) {
+        while self.changes.front().map(|c| c.tick + self.frames <= tick).unwrap_or(false) {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(StructuralChange{ tick, system: self.system, entity, op });
+    }
+
+    /// Sets the system to which new changes are attributed.
+    /// 
+    /// **Arguments**
+    ///  * `system`: The name of the system, or None to stop attributing.
+    /// 
+    /// **Returns**  
+    /// The system to which changes were attributed before.
+    #[inline]
+    pub(crate) fn set_system(&mut self, system: Option<&'static str>) -> Option<&'static str> {
+        std::mem::replace(&mut self.system, system)
+    }
+
+
+
+    /// Returns an iterator over all recorded changes, oldest first.
+    /// 
+    /// Note that changes are only dropped when new ones are recorded, so this may include changes slightly older than the configured number of frames.
+    #[inline]
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, StructuralChange> { self.changes.iter() }
+
+    /// Returns an iterator over all recorded changes of the given entity, oldest first.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to return the changes of.
+    #[inline]
+    pub fn of_entity(&self, entity: Entity) -> impl Iterator<Item = &StructuralChange> {
+        self.changes.iter().filter(move |c| c.entity == entity)
+    }
+
+    /// Returns the number of frames (change ticks) this Journal keeps changes of.
+    #[inline]
+    pub fn frames(&self) -> u64 { self.frames }
+
+    /// Returns the number of recorded changes.
+    #[inline]
+    pub fn len(&self) -> usize { self.changes.len() }
+
+    /// Returns whether any changes have been recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.changes.is_empty() }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    26 Mar 2022, 10:31:02
 //  Last edited:
-//    06 Aug 2022, 16:23:27
+//    16 Oct 2026, 00:49:13
 //  Auto updated?
 //    Yes
 // 
@@ -18,14 +18,58 @@
 // 
 
 // Declare the modules
+pub mod errors;
+pub mod bundle;
 pub mod spec;
 pub mod list;
+pub mod relation;
+pub mod hierarchy;
+pub mod oneshot;
+pub mod commands;
+pub mod events;
+pub mod resources;
+pub mod previous;
+pub mod query;
+pub mod fsm;
+pub mod snapshot;
+pub mod schema;
+pub mod wire;
+pub mod view;
+pub mod split;
+pub mod extract;
+pub mod checksum;
+pub mod quota;
+pub mod orphan;
+pub mod journal;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod system;
 
 
 // Bring some components into the general package namespace (possibly by aliasing them)
-pub use spec::{Component, Entity};
-pub use list::ComponentList;
+pub use errors::EcsError;
+pub use bundle::Bundle;
+pub use spec::{Component, Entity, EntityHashMap, EntityReserver, EntityHashSet, Pod, Poolable, Validate, WeakEntity};
+pub use list::{join, ComponentList, Join};
+pub use relation::Relation;
+pub use hierarchy::{ChildOf, TraversalOrder};
+pub use oneshot::SystemId;
+pub use commands::CommandBuffer;
+pub use events::{EventRetention, Events, ManualEventReader};
+pub use resources::{Res, ResMut};
+pub use previous::Previous;
+pub use query::QueryBuilder;
+pub use fsm::{StateChanged, StateMachine, Transitions};
+pub use snapshot::{QuerySnapshot, Snapshot};
+pub use schema::{ComponentDescription, ComponentInfo, ComponentSchema, Describe, FieldInfo};
+pub use wire::{WireCodec, WireReader, WireWriter};
+pub use view::WorldView;
+pub use split::{Read, Write};
+pub use extract::{EntityMapping, ExtractIds};
+pub use checksum::{desync_report, StateHash};
+pub use quota::{Quota, QuotaRejection, QuotaWarning};
+pub use orphan::{OrphanedComponent, OrphanPolicy};
+pub use journal::{Journal, StructuralChange, StructuralOp};
 pub use system::Ecs;
 
 
@@ -52,6 +96,147 @@ macro_rules! to_component_list_mut {
     };
 }
 
+/// Spawns a new entity with the given components.
+/// 
+/// The component types must already be registered. Evaluates to the new Entity.
+/// 
+/// # Example
+/// ```ignore
+/// let player: Entity = spawn!(ecs, { Position(0.0, 0.0), Velocity(1.0, 0.0), Player });
+/// ```
+#[macro_export]
+macro_rules! spawn {
+    ($ecs:expr, { $($component:expr),* $(,)? }) => {
+        {
+            let ecs: &$crate::Ecs = &$ecs;
+            let entity: $crate::Entity = ecs.add_entity();
+            $(ecs.add_component(entity, $component);)*
+            entity
+        }
+    };
+}
+
+/// Creates a new Ecs with the given component types registered, and optionally spawns entities in it.
+/// 
+/// Without entities, evaluates to the `Rc<RefCell<Ecs>>`. With entities (listed after a semicolon, see `spawn!`), evaluates to a tuple of the Ecs and an array with the spawned entities in order.
+/// 
+/// # Example
+/// ```ignore
+/// let ecs = world!(Position, Velocity, Player);
+/// let (ecs, [ player, rock ]) = world!(Position, Velocity, Player;
+///     { Position(0.0, 0.0), Velocity(1.0, 0.0), Player },
+///     { Position(5.0, 2.0) },
+/// );
+/// ```
+#[macro_export]
+macro_rules! world {
+    ($($ctype:ty),* $(,)?) => {
+        {
+            let ecs = $crate::Ecs::new(64);
+            $($crate::Ecs::register::<$ctype>(&ecs);)*
+            ecs
+        }
+    };
+
+    ($($ctype:ty),* ; $({ $($component:expr),* $(,)? }),* $(,)?) => {
+        {
+            let ecs = $crate::world!($($ctype),*);
+            let entities = {
+                let ecs_ref = ecs.borrow();
+                [$($crate::spawn!(ecs_ref, { $($component),* })),*]
+            };
+            (ecs, entities)
+        }
+    };
+}
+
+/// Runs the given body for every entity that has (or doesn't have) the given components.
+/// 
+/// The body is written like a closure, whose parameters define the query:
+///  * `name: Entity` binds the matched entity;
+///  * `name: &T` and `name: &mut T` bind the entity's component of type `T` (and skip entities without it);
+///  * `_: With<T>` and `_: Without<T>` only filter on (the absence of) a component type.
+/// 
+/// Every component list is locked once for the duration of the query, so the same type may only be used in one parameter. Unlike in a real closure, the body runs inside a loop; use `continue` to skip to the next entity, or `break` to stop early.
+/// 
+/// # Example
+/// ```ignore
+/// query!(ecs, |e: Entity, pos: &mut Position, vel: &Velocity, _: With<Player>| {
+///     pos.0 += vel.0;
+/// });
+/// ```
+#[macro_export]
+macro_rules! query {
+    // Entry point
+    ($ecs:expr, | $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [__entity] [] [] [] []; $($rest)*)
+    };
+
+    // Parse the parameters one-by-one
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; $name:ident : Entity , $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$name] [$($r)*] [$($w)*] [$($wi)*] [$($wo)*]; $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; $name:ident : Entity | $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$name] [$($r)*] [$($w)*] [$($wi)*] [$($wo)*]; | $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; $name:ident : &mut $ctype:ty , $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$ent] [$($r)*] [$($w)* ($name $ctype)] [$($wi)*] [$($wo)*]; $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; $name:ident : &mut $ctype:ty | $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$ent] [$($r)*] [$($w)* ($name $ctype)] [$($wi)*] [$($wo)*]; | $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; $name:ident : & $ctype:ty , $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$ent] [$($r)* ($name $ctype)] [$($w)*] [$($wi)*] [$($wo)*]; $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; $name:ident : & $ctype:ty | $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$ent] [$($r)* ($name $ctype)] [$($w)*] [$($wi)*] [$($wo)*]; | $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; _ : With<$ctype:ty> , $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$ent] [$($r)*] [$($w)*] [$($wi)* (with $ctype)] [$($wo)*]; $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; _ : With<$ctype:ty> | $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$ent] [$($r)*] [$($w)*] [$($wi)* (with $ctype)] [$($wo)*]; | $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; _ : Without<$ctype:ty> , $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$ent] [$($r)*] [$($w)*] [$($wi)*] [$($wo)* (without $ctype)]; $($rest)*)
+    };
+    (@parse [$ecs:expr] [$ent:ident] [$($r:tt)*] [$($w:tt)*] [$($wi:tt)*] [$($wo:tt)*]; _ : Without<$ctype:ty> | $($rest:tt)*) => {
+        $crate::query!(@parse [$ecs] [$ent] [$($r)*] [$($w)*] [$($wi)*] [$($wo)* (without $ctype)]; | $($rest)*)
+    };
+
+    // Generate the code once all parameters have been parsed
+    (@parse [$ecs:expr] [$ent:ident] [$(($rname:ident $rtype:ty))*] [$(($wname:ident $wtype:ty))*] [$(($winame:ident $witype:ty))*] [$(($woname:ident $wotype:ty))*]; | $body:expr) => {
+        {
+            let ecs: &$crate::Ecs = &$ecs;
+
+            // Lock all lists once
+            $(let $rname = ecs.list_component::<$rtype>();)*
+            $(#[allow(unused_mut)] let mut $wname = ecs.list_component_mut::<$wtype>();)*
+            $(let $winame = ecs.list_component::<$witype>();)*
+            $(let $woname = ecs.list_component::<$wotype>();)*
+
+            // Walk the smallest list we need components of, or else all entities
+            let candidates: ::std::vec::Vec<$crate::Entity> = {
+                let lists: ::std::vec::Vec<&dyn $crate::spec::ComponentListBase> = ::std::vec![$(&*$rname as &dyn $crate::spec::ComponentListBase,)* $(&*$wname as &dyn $crate::spec::ComponentListBase,)* $(&*$winame as &dyn $crate::spec::ComponentListBase,)*];
+                match lists.iter().min_by_key(|l| l.len()) {
+                    Some(list) => (0..list.len()).filter_map(|i| list.get_entity(i)).collect(),
+                    None       => ecs.entities(),
+                }
+            };
+
+            // Run the body for every entity that matches
+            #[allow(unused_variables)]
+            for $ent in candidates {
+                $(if $winame.get($ent).is_none() { continue; })*
+                $(if $woname.get($ent).is_some() { continue; })*
+                $(let $rname: &$rtype = match $rname.get($ent) { Some(c) => c, None => continue };)*
+                $(let $wname: &mut $wtype = match $wname.get_mut($ent) { Some(c) => c, None => continue };)*
+                $body;
+            }
+        }
+    };
+}
+
 
 // Define some crate-local macros
 /// Performs a `log`-crate `debug`, but only if that feature is defined
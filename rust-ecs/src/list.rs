@@ -4,7 +4,7 @@
 //  Created:
 //    26 Mar 2022, 10:32:36
 //  Last edited:
-//    06 Aug 2022, 16:19:32
+//    16 Oct 2026, 00:18:34
 //  Auto updated?
 //    Yes
 // 
@@ -13,11 +13,211 @@
 //!   but
 // 
 
+use std::alloc::Layout;
 use std::any::{Any, TypeId};
-use std::collections::HashMap;
 use std::ops::{Index, IndexMut};
 
-use crate::spec::{Component, ComponentListBase, Entity};
+use crate::spec::{Component, ComponentListBase, Entity, EntityHashMap, Pod};
+
+
+/***** UNIT TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Simple component to test with
+    #[derive(Debug)]
+    struct Value(u32);
+    impl Component for Value {}
+
+    /// A single operation on a ComponentList.
+    #[derive(Clone, Debug)]
+    enum Op {
+        Insert(u64, u32),
+        Remove(u64),
+        Delete(u64),
+        Swap(u64, u64),
+        Clear,
+    }
+
+    /// Generates random operations on a small set of entities, so that they collide often.
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            4 => (1..16u64, any::<u32>()).prop_map(|(e, v)| Op::Insert(e, v)),
+            2 => (1..16u64).prop_map(Op::Remove),
+            2 => (1..16u64).prop_map(Op::Delete),
+            1 => (1..16u64, 1..16u64).prop_map(|(a, b)| Op::Swap(a, b)),
+            1 => Just(Op::Clear),
+        ]
+    }
+
+    /// Applies the given operations to both a ComponentList and a HashMap, and checks they agree after every step.
+    fn check(mut list: ComponentList<Value>, ops: Vec<Op>) -> Result<(), TestCaseError> {
+        let mut model: HashMap<u64, u32> = HashMap::new();
+        for op in ops {
+            match op {
+                Op::Insert(e, v) => {
+                    let full: bool = list.fixed_capacity().map(|c| model.len() >= c && !model.contains_key(&e)).unwrap_or(false);
+                    prop_assert_eq!(list.insert(Entity::try_from(e).unwrap(), Value(v)), !full);
+                    if !full { model.insert(e, v); }
+                },
+                Op::Remove(e) => {
+                    prop_assert_eq!(list.remove(Entity::try_from(e).unwrap()).map(|v| v.0), model.remove(&e));
+                },
+                Op::Delete(e) => {
+                    list.delete(Entity::try_from(e).unwrap());
+                    model.remove(&e);
+                },
+                Op::Swap(a, b) => {
+                    let swapped: bool = list.swap(Entity::try_from(a).unwrap(), Entity::try_from(b).unwrap());
+                    prop_assert_eq!(swapped, model.contains_key(&a) || model.contains_key(&b));
+                    let (va, vb) = (model.remove(&a), model.remove(&b));
+                    if let Some(v) = va { model.insert(b, v); }
+                    if let Some(v) = vb { model.insert(a, v); }
+                },
+                Op::Clear => {
+                    list.clear();
+                    model.clear();
+                },
+            }
+
+            // Check the list against the model
+            prop_assert!(list.is_consistent());
+            prop_assert_eq!(list.len(), model.len());
+            for (e, v) in &model {
+                prop_assert_eq!(list.get(Entity::try_from(*e).unwrap()).map(|v| v.0), Some(*v));
+            }
+            let mut values: Vec<u32> = list.iter_entities().map(|(_, v)| v.0).collect();
+            let mut expected: Vec<u32> = model.values().copied().collect();
+            values.sort_unstable();
+            expected.sort_unstable();
+            prop_assert_eq!(values, expected);
+        }
+        Ok(())
+    }
+
+    /// Tests getting multiple components mutably at once
+    #[test]
+    fn test_get_disjoint_mut() {
+        let entities: Vec<Entity> = (1..=4).map(|e| Entity::try_from(e).unwrap()).collect();
+        let mut list: ComponentList<Value> = ComponentList::new(4);
+        for (i, entity) in entities.iter().enumerate().take(3) {
+            list.insert(*entity, Value(i as u32));
+        }
+
+        // Get them out of order, including one that doesn't exist
+        let [ c, d, a ] = list.get_disjoint_mut([ entities[2], entities[3], entities[0] ]);
+        assert!(d.is_none());
+        let (a, c) = (a.unwrap(), c.unwrap());
+        std::mem::swap(&mut a.0, &mut c.0);
+        assert_eq!(list.get(entities[0]).unwrap().0, 2);
+        assert_eq!(list.get(entities[2]).unwrap().0, 0);
+        assert_eq!(list.get(entities[1]).unwrap().0, 1);
+    }
+
+    /// Tests joining two lists, driven by either of them
+    #[test]
+    fn test_join() {
+        #[derive(Debug)]
+        struct Other(u32);
+        impl Component for Other {}
+
+        let entities: Vec<Entity> = (1..=5).map(|e| Entity::try_from(e).unwrap()).collect();
+        let mut values: ComponentList<Value> = ComponentList::new(4);
+        let mut others: ComponentList<Other> = ComponentList::new(4);
+        for (i, entity) in entities.iter().enumerate() {
+            values.insert(*entity, Value(i as u32));
+            if i % 2 == 0 { others.insert(*entity, Other(10 * i as u32)); }
+        }
+
+        // Both orders yield the same pairs
+        let mut pairs: Vec<(Entity, u32, u32)> = join(&values, &others).map(|(e, v, o)| (e, v.0, o.0)).collect();
+        pairs.sort_by_key(|(e, _, _)| *e);
+        assert_eq!(pairs, vec![ (entities[0], 0, 0), (entities[2], 2, 20), (entities[4], 4, 40) ]);
+        let mut pairs: Vec<(Entity, u32, u32)> = join(&others, &values).map(|(e, o, v)| (e, v.0, o.0)).collect();
+        pairs.sort_by_key(|(e, _, _)| *e);
+        assert_eq!(pairs, vec![ (entities[0], 0, 0), (entities[2], 2, 20), (entities[4], 4, 40) ]);
+    }
+
+    /// Tests viewing plain old data components as bytes
+    #[test]
+    fn test_as_bytes() {
+        #[derive(Clone, Copy, Debug)]
+        #[repr(C)]
+        struct Pos([f32; 2]);
+        impl Component for Pos {}
+        unsafe impl Pod for Pos {}
+
+        let mut list: ComponentList<Pos> = ComponentList::new(4);
+        list.insert(Entity::try_from(1).unwrap(), Pos([ 1.0, 2.0 ]));
+        list.insert(Entity::try_from(2).unwrap(), Pos([ 3.0, 4.0 ]));
+        let bytes: &[u8] = list.as_bytes();
+        assert_eq!(bytes.len(), 16);
+        let floats: Vec<f32> = bytes.chunks_exact(4).map(|b| f32::from_ne_bytes(b.try_into().unwrap())).collect();
+        assert_eq!(floats, vec![ 1.0, 2.0, 3.0, 4.0 ]);
+    }
+
+    /// Tests that getting the same entity twice panics
+    #[test]
+    #[should_panic]
+    fn test_get_disjoint_mut_duplicate() {
+        let entity: Entity = Entity::try_from(1).unwrap();
+        let mut list: ComponentList<Value> = ComponentList::new(4);
+        list.insert(entity, Value(0));
+        let _ = list.get_disjoint_mut([ entity, entity ]);
+    }
+
+    proptest! {
+        /// Tests that random operations keep the mappings of a growing list consistent
+        #[test]
+        fn test_random_ops(ops in proptest::collection::vec(op(), 0..128)) {
+            check(ComponentList::new(4), ops)?;
+        }
+
+        /// Tests that random operations keep the mappings of fixed and pooled lists consistent
+        #[test]
+        fn test_random_ops_fixed_pooled(ops in proptest::collection::vec(op(), 0..128)) {
+            check(ComponentList::with_fixed_capacity(8), ops.clone())?;
+            check(ComponentList::new(4).with_pool(4), ops)?;
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY FUNCTIONS *****/
+/// Iterates over all entities that have a component in both of the given lists.
+/// 
+/// The smaller list drives the iteration, and the other is only probed for every entity in it. This is the building block for joins on two component types; use `query!` for more complex queries.
+/// 
+/// **Generic Types**
+///  * `A`: The Component type of the first list.
+///  * `B`: The Component type of the second list.
+/// 
+/// **Arguments**
+///  * `a`: The first list to join.
+///  * `b`: The second list to join.
+/// 
+/// **Returns**  
+/// An iterator over (Entity, &A, &B) triples, in the order of the smaller list.
+#[inline]
+pub fn join<'a, A: Component, B: Component>(a: &'a ComponentList<A>, b: &'a ComponentList<B>) -> Join<'a, A, B> {
+    Join {
+        a,
+        b,
+        a_drives : a.len() <= b.len(),
+        index    : 0,
+    }
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -31,11 +231,17 @@ where
     T: Component,
 {
     /// Maps entity names to indices
-    e_to_i : HashMap<Entity, usize>,
-    /// Maps indices to entity names
-    i_to_e : HashMap<usize, Entity>,
+    e_to_i    : EntityHashMap<usize>,
+    /// Maps indices to entity names (i.e., the entity of every element in `data`)
+    i_to_e    : Vec<Entity>,
     /// Stores the components
-    data   : Vec<T>,
+    data      : Vec<T>,
+    /// If set, the list never grows beyond (and never reallocates for) this many components
+    fixed     : Option<usize>,
+    /// Components that have been removed, and may be reused
+    pool      : Vec<T>,
+    /// The maximum number of components in the pool (0 disables pooling)
+    pool_size : usize,
 }
 
 impl<T: Component> ComponentList<T> {
@@ -45,12 +251,45 @@ impl<T: Component> ComponentList<T> {
     ///  * `initial_capacity`: The initial capacity of the list. Used for optimization purposes.
     pub(crate) fn new(initial_capacity: usize) -> Self {
         ComponentList {
-            e_to_i : HashMap::with_capacity(initial_capacity),
-            i_to_e : HashMap::with_capacity(initial_capacity),
-            data   : Vec::with_capacity(initial_capacity),
+            e_to_i    : EntityHashMap::with_capacity_and_hasher(initial_capacity, Default::default()),
+            i_to_e    : Vec::with_capacity(initial_capacity),
+            data      : Vec::with_capacity(initial_capacity),
+            fixed     : None,
+            pool      : vec![],
+            pool_size : 0,
+        }
+    }
+
+    /// Constructor for a ComponentList with a fixed capacity.
+    /// 
+    /// Such a list allocates once and never reallocates, so components stay at the same address. The only exception is removal, which moves the last component into the removed one's place.
+    /// 
+    /// **Arguments**
+    ///  * `capacity`: The maximum number of components in the list.
+    pub(crate) fn with_fixed_capacity(capacity: usize) -> Self {
+        ComponentList {
+            e_to_i    : EntityHashMap::with_capacity_and_hasher(capacity, Default::default()),
+            i_to_e    : Vec::with_capacity(capacity),
+            data      : Vec::with_capacity(capacity),
+            fixed     : Some(capacity),
+            pool      : vec![],
+            pool_size : 0,
         }
     }
 
+    /// Enables pooling for this list.
+    /// 
+    /// Components that are deleted (e.g., because their entity is removed), cleared or overwritten are then kept in a pool instead of being dropped, so their allocations may be reused by `ComponentList::take_pooled()`.
+    /// 
+    /// **Arguments**
+    ///  * `pool_size`: The maximum number of components to keep around.
+    #[inline]
+    pub(crate) fn with_pool(mut self, pool_size: usize) -> Self {
+        self.pool_size = pool_size;
+        self.pool.reserve(pool_size);
+        self
+    }
+
     /// Returns the identifier for this ComponentList type.
     #[inline]
     pub(crate) fn id() -> TypeId
@@ -77,25 +316,36 @@ impl<T: Component> ComponentList<T> {
     /// **Arguments**
     ///  * `entity`: The Entity to add the data for.
     ///  * `data`: The data to add for the Entity.
+    /// 
+    /// **Returns**  
+    /// True if the data was inserted, or false if this is a fixed-capacity list that is full.
     #[inline]
-    pub(crate) fn insert(&mut self, entity: Entity, data: T) {
+    pub(crate) fn insert(&mut self, entity: Entity, data: T) -> bool {
         // Do two different things depending on if it exists or not
         match self.e_to_i.get(&entity) {
             Some(index) => {
                 // Overwrite the value
-                self.data[*index] = data;
+                let old: T = std::mem::replace(&mut self.data[*index], data);
+                self.recycle(old);
             },
             None => {
+                // Refuse to grow fixed-capacity lists
+                if let Some(capacity) = self.fixed {
+                    if self.data.len() >= capacity { return false; }
+                }
+
                 // Add the mapping
                 let index = self.data.len();
                 self.e_to_i.insert(entity, index);
-                self.i_to_e.insert(index, entity);
+                self.i_to_e.push(entity);
 
                 // Add the data itself
                 if self.data.len() >= self.data.capacity() { self.data.reserve(self.data.capacity()); }
                 self.data.push(data);
             }
         }
+        self.strict_check();
+        true
     }
 
     /// Gets the component for the given entity (as immuteable).
@@ -126,6 +376,42 @@ impl<T: Component> ComponentList<T> {
         }
     }
 
+    /// Gets the components for multiple, distinct entities at once (as muteable).
+    /// 
+    /// This allows updating pairs of components in the same list (e.g., a spring between two entities' positions) without cloning one of them.
+    /// 
+    /// **Generic Types**
+    ///  * `N`: The number of entities to get the components of.
+    /// 
+    /// **Arguments**
+    ///  * `entities`: The entities to get the components of.
+    /// 
+    /// **Returns**  
+    /// A muteable reference to the component of every entity (in the same order), or None for the entities that don't have one.
+    /// 
+    /// **Panics**  
+    /// This function panics if the same entity is given more than once.
+    pub fn get_disjoint_mut<const N: usize>(&mut self, entities: [Entity; N]) -> [Option<&mut T>; N] {
+        for (i, entity) in entities.iter().enumerate() {
+            if entities[..i].contains(entity) { panic!("Entity {} is given more than once to ComponentList::get_disjoint_mut()", entity); }
+        }
+
+        // Visit the components in order of their index, so we can keep splitting off the remainder of the list
+        let mut order: [(usize, usize); N] = std::array::from_fn(|i| (self.e_to_i.get(&entities[i]).copied().unwrap_or(usize::MAX), i));
+        order.sort_unstable();
+        let mut result: [Option<&mut T>; N] = std::array::from_fn(|_| None);
+        let mut rest: &mut [T] = &mut self.data;
+        let mut offset: usize = 0;
+        for (index, i) in order {
+            if index == usize::MAX { break; }
+            let (component, tail) = std::mem::take(&mut rest)[index - offset..].split_first_mut().expect("Entity index is out of bounds");
+            result[i] = Some(component);
+            rest = tail;
+            offset = index + 1;
+        }
+        result
+    }
+
     /// Removes the component for an entity.
     /// 
     /// **Arguments**
@@ -140,19 +426,19 @@ impl<T: Component> ComponentList<T> {
             Some(index) => {
                 // Remove from the mappings
                 self.e_to_i.remove(&entity);
-                self.i_to_e.remove(&index);
+                self.i_to_e.swap_remove(index);
 
                 // Next, remove the data itself
                 let to_return = self.data.swap_remove(index);
 
                 // If there is a last value that was swapped, update the value of the last element to point to this element instead
-                if self.data.len() > 0 {
-                    let last_entity = self.i_to_e.remove(&self.data.len()).expect("Last element in list is not mapped in index-to-entity map");
-                    self.i_to_e.insert(index, last_entity);
+                if index < self.data.len() {
+                    let last_entity = self.i_to_e[index];
                     *self.e_to_i.get_mut(&last_entity).expect("Last element in list is not mapped in entity-to-index map") = index;
                 }
 
                 // Done
+                self.strict_check();
                 Some(to_return)
             },
             None => None,
@@ -161,6 +447,108 @@ impl<T: Component> ComponentList<T> {
 
 
 
+    /// Swaps the components of two entities.  
+    /// This is done by only updating the internal mappings, so no data is moved.
+    /// 
+    /// If only one of the entities has a component, it is moved to the other entity.
+    /// 
+    /// **Arguments**
+    ///  * `a`: The first Entity to swap the data of.
+    ///  * `b`: The second Entity to swap the data of.
+    /// 
+    /// **Returns**  
+    /// True if at least one of the entities had a component (i.e., something was swapped), or false otherwise.
+    pub(crate) fn swap(&mut self, a: Entity, b: Entity) -> bool {
+        // Remove both of the mappings first
+        let a_index: Option<usize> = self.e_to_i.remove(&a);
+        let b_index: Option<usize> = self.e_to_i.remove(&b);
+        if a_index.is_none() && b_index.is_none() { return false; }
+
+        // Re-insert them crosswise
+        if let Some(index) = a_index {
+            self.e_to_i.insert(b, index);
+            self.i_to_e[index] = b;
+        }
+        if let Some(index) = b_index {
+            self.e_to_i.insert(a, index);
+            self.i_to_e[index] = a;
+        }
+
+        // Done
+        self.strict_check();
+        true
+    }
+
+    /// Removes the components of all entities, but keeps the allocated memory.
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.e_to_i.clear();
+        self.i_to_e.clear();
+        let room: usize = self.pool_size.saturating_sub(self.pool.len());
+        self.pool.extend(self.data.drain(..).take(room));
+        self.data.clear();
+    }
+
+
+
+    /// Puts the given component in the pool, if pooling is enabled and the pool isn't full yet.
+    /// 
+    /// **Arguments**
+    ///  * `component`: The component to recycle. It is dropped if it cannot be pooled.
+    #[inline]
+    fn recycle(&mut self, component: T) {
+        if self.pool.len() < self.pool_size { self.pool.push(component); }
+    }
+
+    /// Takes a component from the pool.
+    /// 
+    /// **Returns**  
+    /// A previously removed component (which still has to be reset), or None if the pool is empty.
+    #[inline]
+    pub(crate) fn take_pooled(&mut self) -> Option<T> { self.pool.pop() }
+
+    /// Returns the number of components currently in the pool.
+    #[inline]
+    pub fn pooled(&self) -> usize { self.pool.len() }
+
+
+
+    /// Returns the number of components in the ComponentList.
+    #[inline]
+    pub fn len(&self) -> usize { self.data.len() }
+
+    /// Returns the fixed capacity of the ComponentList, or None if it grows as needed.
+    #[inline]
+    pub fn fixed_capacity(&self) -> Option<usize> { self.fixed }
+
+    /// Returns whether the ComponentList has any components.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.data.is_empty() }
+
+    /// Checks whether the internal mappings of the ComponentList are consistent.
+    /// 
+    /// That is, every component has exactly one entity mapped to it (in both directions), and a fixed-capacity list does not exceed its capacity. This should always be the case; it is meant for tests and fuzzing.
+    /// 
+    /// **Returns**  
+    /// True if the mappings are consistent, or false if the list is corrupted.
+    pub fn is_consistent(&self) -> bool {
+        if self.e_to_i.len() != self.data.len() || self.i_to_e.len() != self.data.len() { return false; }
+        if let Some(capacity) = self.fixed {
+            if self.data.len() > capacity { return false; }
+        }
+        self.i_to_e.iter().enumerate().all(|(i, entity)| self.e_to_i.get(entity) == Some(&i))
+    }
+
+    /// Asserts that the internal mappings of the ComponentList are consistent, but only if the `strict-checks` feature is enabled and this is a debug build.
+    /// 
+    /// **Panics**  
+    /// This function panics if the mappings are inconsistent (see `ComponentList::is_consistent()`).
+    #[inline]
+    fn strict_check(&self) {
+        #[cfg(feature = "strict-checks")]
+        debug_assert!(self.is_consistent(), "Mappings of ComponentList<{}> are inconsistent", std::any::type_name::<T>());
+    }
+
     /// Returns an iterator for the ComponentList.
     /// 
     /// # Returns
@@ -168,12 +556,63 @@ impl<T: Component> ComponentList<T> {
     #[inline]
     pub fn iter(&self) -> std::slice::Iter<T> { self.data.iter() }
 
+    /// Returns an iterator over the ComponentList that also yields the entity to which each component belongs.
+    /// 
+    /// # Returns
+    /// A new iterator over (Entity, component) pairs.
+    #[inline]
+    pub fn iter_entities(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.i_to_e.iter().copied().zip(self.data.iter())
+    }
+
     /// Returns a (muteable) iterator for the ComponentList.
     /// 
     /// # Returns
     /// A new iterator for the internal Vector.
     #[inline]
     pub fn iter_mut(&mut self) -> std::slice::IterMut<T> { self.data.iter_mut() }
+
+
+
+    /// Calls the given closure for every component in the list, together with the entity it belongs to.
+    /// 
+    /// This walks the dense storage directly, and is thus the fastest way to visit every component.
+    /// 
+    /// **Arguments**
+    ///  * `f`: The closure to call for every (Entity, component) pair.
+    #[inline]
+    pub fn for_each(&self, mut f: impl FnMut(Entity, &T)) {
+        for (entity, component) in self.i_to_e.iter().zip(self.data.iter()) {
+            f(*entity, component);
+        }
+    }
+
+    /// Calls the given closure for every component in the list (as muteable), together with the entity it belongs to.
+    /// 
+    /// This walks the dense storage directly, and is thus the fastest way to update every component.
+    /// 
+    /// **Arguments**
+    ///  * `f`: The closure to call for every (Entity, component) pair.
+    #[inline]
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(Entity, &mut T)) {
+        for (entity, component) in self.i_to_e.iter().zip(self.data.iter_mut()) {
+            f(*entity, component);
+        }
+    }
+}
+
+impl<T: Pod> ComponentList<T> {
+    /// Returns the components in this list as raw bytes, in the same order as `ComponentList::iter()`.
+    /// 
+    /// Because the components are stored densely, this can be copied into a GPU buffer as-is. Every component takes `std::mem::size_of::<T>()` bytes.
+    /// 
+    /// **Returns**  
+    /// A byte slice covering all components.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `Pod` guarantees that `T` has no padding and consists only of plain data, so every byte of the initialized components is initialized and may be read as a `u8`. The length covers exactly the initialized elements, and `u8` has no alignment requirements.
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr() as *const u8, std::mem::size_of_val(self.data.as_slice())) }
+    }
 }
 
 impl<T> ComponentListBase for ComponentList<T>
@@ -206,6 +645,12 @@ where
         ComponentList::<T>::type_name()
     }
 
+    /// Returns the memory layout of a single component in this list.
+    #[inline]
+    fn layout(&self) -> Layout {
+        Layout::new::<T>()
+    }
+
 
 
     /// Get the index from an entity.  
@@ -231,9 +676,33 @@ where
     /// The entity that resides at the given index if the index is in range, or None otherwise.
     #[inline]
     fn get_entity(&self, index: usize) -> Option<Entity> {
-        self.i_to_e.get(&index).map(|entity| *entity)
+        self.i_to_e.get(index).copied()
     }
 
+    /// Returns the component of the given entity as a type-erased reference.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to get the component of.
+    /// 
+    /// **Returns**  
+    /// The component (which may be downcasted to its concrete type) if the entity has one in this list, or None otherwise.
+    #[inline]
+    fn get_any(&self, entity: Entity) -> Option<&dyn Any> {
+        self.get(entity).map(|c| c as &dyn Any)
+    }
+
+    /// Returns the number of components in the list.
+    #[inline]
+    fn len(&self) -> usize { self.data.len() }
+
+    /// Returns whether the list has any components.
+    #[inline]
+    fn is_empty(&self) -> bool { self.data.is_empty() }
+
+    /// Checks whether the internal mappings of the list are consistent.
+    #[inline]
+    fn is_consistent(&self) -> bool { ComponentList::<T>::is_consistent(self) }
+
 
 
     /// Deletes the given entity if it existed from the internal list.
@@ -242,7 +711,15 @@ where
     ///  * `entity`: The Entity to remove the data of.
     #[inline]
     fn delete(&mut self, entity: Entity) {
-        self.remove(entity);
+        if let Some(component) = self.remove(entity) {
+            self.recycle(component);
+        }
+    }
+
+    /// Deletes all components from the internal list.
+    #[inline]
+    fn clear(&mut self) {
+        ComponentList::<T>::clear(self);
     }
 }
 
@@ -292,3 +769,48 @@ impl <'a, T: Component> IntoIterator for &'a mut ComponentList<T> {
     #[inline]
     fn into_iter(self) -> Self::IntoIter { self.data.iter_mut() }
 }
+
+
+
+/// Iterates over all entities that have a component in both of two lists, as created by `join()`.
+/// 
+/// **Generic Types**
+///  * `A`: The Component type of the first list.
+///  * `B`: The Component type of the second list.
+pub struct Join<'a, A: Component, B: Component> {
+    /// The first list to join.
+    a        : &'a ComponentList<A>,
+    /// The second list to join.
+    b        : &'a ComponentList<B>,
+    /// Whether the first list drives the iteration (i.e., it is the smaller one).
+    a_drives : bool,
+    /// The index of the next component in the driving list.
+    index    : usize,
+}
+
+impl<'a, A: Component, B: Component> Iterator for Join<'a, A, B> {
+    type Item = (Entity, &'a A, &'a B);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.a_drives {
+            while self.index < self.a.data.len() {
+                let (entity, a): (Entity, &'a A) = (self.a.i_to_e[self.index], &self.a.data[self.index]);
+                self.index += 1;
+                if let Some(b) = self.b.get(entity) { return Some((entity, a, b)); }
+            }
+        } else {
+            while self.index < self.b.data.len() {
+                let (entity, b): (Entity, &'a B) = (self.b.i_to_e[self.index], &self.b.data[self.index]);
+                self.index += 1;
+                if let Some(a) = self.a.get(entity) { return Some((entity, a, b)); }
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining: usize = if self.a_drives { self.a.data.len() } else { self.b.data.len() } - self.index;
+        (0, Some(remaining))
+    }
+}
@@ -0,0 +1,91 @@
+//  ONESHOT.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:46:37
+//  Last edited:
+//    15 Oct 2026, 23:46:37
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines one-shot systems, which are registered in the Ecs once and
+//!   may then be run on demand by their ID.
+// 
+
+use std::any::Any;
+use std::fmt::{Debug, Formatter, Result as FResult};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use crate::system::Ecs;
+
+
+/***** AUXILLARY *****/
+/// A type-erased one-shot system as it is stored in the Ecs.
+pub(crate) type BoxedSystem = Box<dyn FnMut(&Ecs, Box<dyn Any>) -> Box<dyn Any>>;
+
+
+
+
+
+/***** LIBRARY *****/
+/// Identifies a one-shot system registered with `Ecs::register_system()`.
+/// 
+/// **Generic Types**
+///  * `I`: The type of the input the system takes.
+///  * `O`: The type of the output the system returns.
+pub struct SystemId<I = (), O = ()> {
+    /// The raw identifier of the system.
+    id     : u64,
+    /// Binds the input and output types to this identifier.
+    _types : PhantomData<fn(I) -> O>,
+}
+
+impl<I, O> SystemId<I, O> {
+    /// Constructor for the SystemId.
+    /// 
+    /// **Arguments**
+    ///  * `id`: The raw identifier of the system.
+    #[inline]
+    pub(crate) fn new(id: u64) -> Self {
+        Self {
+            id,
+            _types : PhantomData,
+        }
+    }
+
+
+
+    /// Returns the raw identifier of the system.
+    #[inline]
+    pub fn id(&self) -> u64 { self.id }
+}
+
+impl<I, O> Clone for SystemId<I, O> {
+    #[inline]
+    fn clone(&self) -> Self { *self }
+}
+
+impl<I, O> Copy for SystemId<I, O> {}
+
+impl<I, O> Debug for SystemId<I, O> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        write!(f, "SystemId({})", self.id)
+    }
+}
+
+impl<I, O> Eq for SystemId<I, O> {}
+
+impl<I, O> Hash for SystemId<I, O> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<I, O> PartialEq for SystemId<I, O> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.id == other.id }
+}
@@ -0,0 +1,40 @@
+//  ORPHAN.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:19:11
+//  Last edited:
+//    16 Oct 2026, 00:19:11
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines the policy and events for detecting (and cleaning up)
+//!   orphaned components, i.e., components whose entity no longer exists.
+// 
+
+use crate::spec::Entity;
+
+
+/***** LIBRARY *****/
+/// Decides what `Ecs::clean_orphans()` does with the orphaned components it finds.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OrphanPolicy {
+    /// Only reports the orphans (as `OrphanedComponent` events), leaving them in place.
+    Report,
+    /// Reports the orphans and removes them from their component lists.
+    Remove,
+}
+
+
+
+/// The event that is sent (in an `Events<OrphanedComponent>` resource, if it exists) for every orphaned component found by `Ecs::clean_orphans()`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OrphanedComponent {
+    /// The (dead) entity to which the component belonged.
+    pub entity    : Entity,
+    /// The name of the component type.
+    pub component : &'static str,
+    /// Whether the component has been removed.
+    pub removed   : bool,
+}
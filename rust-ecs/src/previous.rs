@@ -0,0 +1,76 @@
+//  PREVIOUS.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:51:29
+//  Last edited:
+//    16 Oct 2026, 00:51:19
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines double-buffered components, which remember their value of
+//!   the previous (fixed) tick so it may be used for interpolation.
+// 
+
+use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard};
+
+use crate::spec::{Component, Entity};
+use crate::list::ComponentList;
+use crate::journal::StructuralOp;
+use crate::system::Ecs;
+
+
+/***** LIBRARY FUNCTIONS *****/
+/// Copies the current value of every component of the given type to its `Previous` buffer.
+/// 
+/// This is registered as a hook in the Ecs by `Ecs::register_previous()`. Adding or dropping buffers counts as a structural change (see `Ecs::generation()`), and is recorded in the Journal (if enabled).
+/// 
+/// **Generic Types**
+///  * `T`: The Component type to copy.
+/// 
+/// **Arguments**
+///  * `ecs`: The Ecs to copy the components in.
+pub(crate) fn store_previous<T: 'static + Component + Clone>(ecs: &Ecs) {
+    // Get the lists (if they are still registered)
+    if !ecs.is_registered::<T>() || !ecs.is_registered::<Previous<T>>() { return; }
+    let current: MappedRwLockReadGuard<ComponentList<T>> = ecs.list_component::<T>();
+    let mut previous: MappedRwLockWriteGuard<ComponentList<Previous<T>>> = ecs.list_component_mut::<Previous<T>>();
+
+    // Drop the buffers of components that no longer exist
+    let stale: Vec<Entity> = previous.iter_entities().filter(|(e, _)| current.get(*e).is_none()).map(|(e, _)| e).collect();
+    let mut changed: bool = !stale.is_empty();
+    for entity in stale {
+        previous.remove(entity);
+        ecs.record(entity, StructuralOp::Remove(std::any::type_name::<Previous<T>>()));
+    }
+
+    // Overwrite the others
+    for (entity, value) in current.iter_entities() {
+        match previous.get_mut(entity) {
+            Some(buffer) => { buffer.0.clone_from(value); },
+            None         => {
+                previous.insert(entity, Previous(value.clone()));
+                ecs.record(entity, StructuralOp::Insert(std::any::type_name::<Previous<T>>()));
+                changed = true;
+            },
+        }
+    }
+    if changed { ecs.bump_generation(); }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Stores the value that a component of type `T` had at the previous call to `Ecs::store_previous()`.
+/// 
+/// Use `Ecs::register_previous()` to enable it for a component type, and `Ecs::get_previous()` to read it.
+/// 
+/// **Generic Types**
+///  * `T`: The Component type of which this is the previous value.
+#[derive(Clone, Debug)]
+pub struct Previous<T>(pub T);
+
+impl<T> Component for Previous<T> {}
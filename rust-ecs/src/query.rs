@@ -0,0 +1,253 @@
+//  QUERY.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:54:02
+//  Last edited:
+//    16 Oct 2026, 00:09:47
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines the QueryBuilder, which can be used to find entities by
+//!   component types (and predicates on them) that are only known at
+//!   runtime.
+// 
+
+use std::any::{Any, TypeId};
+
+use parking_lot::RwLockReadGuard;
+
+pub use crate::errors::EcsError as Error;
+use crate::spec::{ComponentListBase, Entity};
+use crate::system::Ecs;
+
+
+/***** AUXILLARY *****/
+/// A type-erased predicate on a single component.
+pub type Predicate = Box<dyn Fn(&dyn Any) -> bool>;
+
+
+
+/// A single term in a QueryBuilder.
+enum Term {
+    /// The entity must have the component, and it must match the predicate (if any).
+    With(TypeId, Option<Predicate>),
+    /// The entity must not have the component.
+    Without(TypeId),
+}
+
+impl Term {
+    /// Returns the component type this term is about.
+    #[inline]
+    fn id(&self) -> TypeId {
+        match self {
+            Term::With(id, _) | Term::Without(id) => *id,
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Finds entities by component types that are only known at runtime, optionally filtered by predicates on their values.
+/// 
+/// This is slower than accessing the lists directly, and is meant for things like an in-game console where the user types the filters. Use `Ecs::component_type_id()` to find the TypeId of a component by name.
+#[derive(Default)]
+pub struct QueryBuilder {
+    /// The terms an entity must match.
+    terms : Vec<Term>,
+}
+
+impl QueryBuilder {
+    /// Constructor for the QueryBuilder, which initially matches all entities.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            terms : vec![],
+        }
+    }
+
+
+
+    /// Only match entities that have the given component.
+    /// 
+    /// **Arguments**
+    ///  * `id`: The TypeId of the Component type.
+    #[inline]
+    pub fn with(mut self, id: TypeId) -> Self {
+        self.terms.push(Term::With(id, None));
+        self
+    }
+
+    /// Only match entities that have the given component, and for which it matches the given predicate.
+    /// 
+    /// **Arguments**
+    ///  * `id`: The TypeId of the Component type.
+    ///  * `predicate`: The predicate, which gets the component as a type-erased reference.
+    #[inline]
+    pub fn with_predicate(mut self, id: TypeId, predicate: impl 'static + Fn(&dyn Any) -> bool) -> Self {
+        self.terms.push(Term::With(id, Some(Box::new(predicate))));
+        self
+    }
+
+    /// Only match entities that do not have the given component.
+    /// 
+    /// **Arguments**
+    ///  * `id`: The TypeId of the Component type.
+    #[inline]
+    pub fn without(mut self, id: TypeId) -> Self {
+        self.terms.push(Term::Without(id));
+        self
+    }
+
+
+
+    /// Runs the query on the given Ecs.
+    /// 
+    /// The relevant component lists are only locked while the query runs, not while the result is being iterated.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to find the entities in.
+    /// 
+    /// **Returns**  
+    /// An iterator over the matching entities, in no particular order.
+    /// 
+    /// **Errors**  
+    /// This function errors if one of the component types has not been registered.
+    pub fn iter(&self, ecs: &Ecs) -> Result<std::vec::IntoIter<Entity>, Error> {
+        let mut result: Vec<Entity> = vec![];
+        self.scan(ecs, |entity| { result.push(entity); true })?;
+        Ok(result.into_iter())
+    }
+
+    /// Counts the entities that match the query, without collecting them.
+    /// 
+    /// A query with a single component type (and no predicate) is answered from the length of its list directly.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to count the entities in.
+    /// 
+    /// **Returns**  
+    /// The number of matching entities.
+    /// 
+    /// **Errors**  
+    /// This function errors if one of the component types has not been registered.
+    pub fn count(&self, ecs: &Ecs) -> Result<usize, Error> {
+        if let [ Term::With(id, None) ] = self.terms.as_slice() {
+            return ecs.list_component_dyn(*id).map(|list| list.len()).ok_or(Error::UnregisteredComponent{ id: *id });
+        }
+        let mut count: usize = 0;
+        self.scan(ecs, |_| { count += 1; true })?;
+        Ok(count)
+    }
+
+    /// Returns whether no entities match the query.
+    /// 
+    /// This stops at the first matching entity, so it is cheap for queries like "are there any enemies left?".
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to check.
+    /// 
+    /// **Returns**  
+    /// True if no entity matches, or false otherwise.
+    /// 
+    /// **Errors**  
+    /// This function errors if one of the component types has not been registered.
+    pub fn is_empty(&self, ecs: &Ecs) -> Result<bool, Error> {
+        let mut empty: bool = true;
+        self.scan(ecs, |_| { empty = false; false })?;
+        Ok(empty)
+    }
+
+    /// Returns whether the given entity matches the query.
+    /// 
+    /// This only probes the relevant lists for the given entity, so it does not depend on the number of entities in the Ecs.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to check.
+    ///  * `entity`: The Entity to check.
+    /// 
+    /// **Returns**  
+    /// True if the entity is alive and matches all terms, or false otherwise.
+    /// 
+    /// **Errors**  
+    /// This function errors if one of the component types has not been registered.
+    pub fn contains(&self, ecs: &Ecs, entity: Entity) -> Result<bool, Error> {
+        if !ecs.is_alive(entity) { return Ok(false); }
+        let lists: Vec<RwLockReadGuard<Box<dyn ComponentListBase>>> = self.lock(ecs)?;
+        Ok(self.matches(&lists, entity))
+    }
+
+
+
+    /// Locks the lists of all terms in the query.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to lock the lists in.
+    /// 
+    /// **Returns**  
+    /// The locked lists, in the same order as the terms.
+    /// 
+    /// **Errors**  
+    /// This function errors if one of the component types has not been registered.
+    fn lock<'a>(&self, ecs: &'a Ecs) -> Result<Vec<RwLockReadGuard<'a, Box<dyn ComponentListBase>>>, Error> {
+        let mut lists: Vec<RwLockReadGuard<Box<dyn ComponentListBase>>> = Vec::with_capacity(self.terms.len());
+        for term in &self.terms {
+            match ecs.list_component_dyn(term.id()) {
+                Some(list) => { lists.push(list); },
+                None       => { return Err(Error::UnregisteredComponent{ id: term.id() }); },
+            }
+        }
+        Ok(lists)
+    }
+
+    /// Returns whether the given entity matches all terms in the query.
+    /// 
+    /// **Arguments**
+    ///  * `lists`: The locked lists, in the same order as the terms.
+    ///  * `entity`: The Entity to check.
+    fn matches(&self, lists: &[RwLockReadGuard<Box<dyn ComponentListBase>>], entity: Entity) -> bool {
+        self.terms.iter().zip(lists.iter()).all(|(term, list)| match term {
+            Term::With(_, predicate) => match list.get_any(entity) {
+                Some(component) => predicate.as_ref().map(|p| p(component)).unwrap_or(true),
+                None            => false,
+            },
+            Term::Without(_) => list.get_any(entity).is_none(),
+        })
+    }
+
+    /// Calls the given closure for every entity that matches the query.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to find the entities in.
+    ///  * `f`: The closure to call for every matching entity. Returning false stops the scan.
+    /// 
+    /// **Errors**  
+    /// This function errors if one of the component types has not been registered.
+    fn scan(&self, ecs: &Ecs, mut f: impl FnMut(Entity) -> bool) -> Result<(), Error> {
+        // Lock all of the lists first
+        let lists: Vec<RwLockReadGuard<Box<dyn ComponentListBase>>> = self.lock(ecs)?;
+
+        // Walk the smallest list we need components of, or else all entities
+        let driver: Option<&RwLockReadGuard<Box<dyn ComponentListBase>>> = self.terms.iter().zip(lists.iter())
+            .filter(|(t, _)| matches!(t, Term::With(_, _)))
+            .map(|(_, l)| l)
+            .min_by_key(|l| l.len());
+        match driver {
+            Some(list) => {
+                for entity in (0..list.len()).filter_map(|i| list.get_entity(i)) {
+                    if self.matches(&lists, entity) && !f(entity) { break; }
+                }
+            },
+            None => {
+                for entity in ecs.entities() {
+                    if self.matches(&lists, entity) && !f(entity) { break; }
+                }
+            },
+        }
+        Ok(())
+    }
+}
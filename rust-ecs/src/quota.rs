@@ -0,0 +1,95 @@
+//  QUOTA.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:15:35
+//  Last edited:
+//    16 Oct 2026, 00:49:13
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines quotas, which limit the number of entities (or components of
+//!   a type) in the Ecs so a runaway spawner cannot exhaust memory.
+// 
+
+
+/***** LIBRARY *****/
+/// Limits the number of entities or components of a type, see `Ecs::set_entity_quota()` and `Ecs::set_component_quota()`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Quota {
+    /// If set, a `QuotaWarning` event is sent when the count grows beyond this number.
+    pub soft : Option<usize>,
+    /// If set, spawning or adding beyond this number fails.
+    pub hard : Option<usize>,
+}
+
+impl Quota {
+    /// Constructor for a Quota without any limits.
+    #[inline]
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the soft limit of this Quota.
+    /// 
+    /// **Arguments**
+    ///  * `limit`: The number beyond which a `QuotaWarning` is sent.
+    #[inline]
+    pub fn with_soft(mut self, limit: usize) -> Self {
+        self.soft = Some(limit);
+        self
+    }
+
+    /// Sets the hard limit of this Quota.
+    /// 
+    /// **Arguments**
+    ///  * `limit`: The number beyond which spawning or adding fails.
+    #[inline]
+    pub fn with_hard(mut self, limit: usize) -> Self {
+        self.hard = Some(limit);
+        self
+    }
+
+
+
+    /// Returns whether one more item would exceed the hard limit.
+    /// 
+    /// **Arguments**
+    ///  * `count`: The current number of items.
+    #[inline]
+    pub(crate) fn is_full(&self, count: usize) -> bool { self.hard.map(|hard| count >= hard).unwrap_or(false) }
+
+    /// Returns the soft limit if the given (new) number of items has just crossed it.
+    /// 
+    /// **Arguments**
+    ///  * `count`: The number of items after adding one.
+    #[inline]
+    pub(crate) fn crossed_soft(&self, count: usize) -> Option<usize> { self.soft.filter(|soft| count == soft + 1) }
+}
+
+
+
+/// The event that is sent (in an `Events<QuotaWarning>` resource, if it exists) when a soft limit is exceeded.
+/// 
+/// It is sent once every time the count grows beyond the limit, not for every item after that.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct QuotaWarning {
+    /// What exceeded its quota: `"entities"`, or the name of a component type.
+    pub what  : &'static str,
+    /// The number of entities or components after the quota was exceeded.
+    pub count : usize,
+    /// The soft limit that was exceeded.
+    pub limit : usize,
+}
+
+/// The event that is sent (in an `Events<QuotaRejection>` resource, if it exists) when entities are discarded because they would exceed a hard limit.
+/// 
+/// This happens for entities that were reserved (see `Ecs::reserve_entity()` and `CommandBuffer::spawn()`), since those cannot refuse to be spawned when they are reserved.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct QuotaRejection {
+    /// What exceeded its quota (always `"entities"` for now).
+    pub what      : &'static str,
+    /// The number of items that were discarded.
+    pub discarded : usize,
+    /// The hard limit that would have been exceeded.
+    pub limit     : usize,
+}
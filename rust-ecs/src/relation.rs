@@ -0,0 +1,120 @@
+//  RELATION.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:44:01
+//  Last edited:
+//    16 Oct 2026, 00:30:42
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines relationships between entities, which are components that
+//!   point to another entity and are cleaned up automatically if that
+//!   entity is removed.
+// 
+
+use std::marker::PhantomData;
+
+use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard};
+
+use crate::spec::{Component, Entity, EntityHashSet};
+use crate::list::ComponentList;
+use crate::journal::StructuralOp;
+use crate::system::Ecs;
+
+
+/***** LIBRARY FUNCTIONS *****/
+/// Returns all relations of the given kind whose target entity no longer exists.
+/// 
+/// This is registered as a hook in the Ecs by `Ecs::register_relation()`.
+/// 
+/// **Generic Types**
+///  * `R`: The kind of relation to check.
+/// 
+/// **Arguments**
+///  * `ecs`: The Ecs to check the relations in.
+/// 
+/// **Returns**  
+/// A list of `(source, target)` pairs of every dangling relation.
+pub(crate) fn dangling_relations<R: 'static>(ecs: &Ecs) -> Vec<(Entity, Entity)> {
+    if !ecs.is_registered::<Relation<R>>() { return vec![]; }
+    let list: MappedRwLockReadGuard<ComponentList<Relation<R>>> = ecs.list_component::<Relation<R>>();
+    list.iter_entities().filter(|(_, r)| !ecs.is_alive(r.target)).map(|(e, r)| (e, r.target)).collect()
+}
+
+/// Removes all relations of the given kind that target one of the given entities.
+/// 
+/// This is registered as a hook in the Ecs by `Ecs::register_relation()`.
+/// 
+/// **Generic Types**
+///  * `R`: The kind of relation to clean up.
+/// 
+/// **Arguments**
+///  * `ecs`: The Ecs to clean the relations in.
+///  * `removed`: The entities that have been removed.
+pub(crate) fn cleanup_relations<R: 'static>(ecs: &Ecs, removed: &EntityHashSet) {
+    // Get the list of relations (if it is still registered)
+    if !ecs.is_registered::<Relation<R>>() { return; }
+    let mut list: MappedRwLockWriteGuard<ComponentList<Relation<R>>> = ecs.list_component_mut::<Relation<R>>();
+
+    // Remove all relations pointing to one of the removed entities
+    let dangling: Vec<Entity> = list.iter_entities().filter(|(_, r)| removed.contains(&r.target)).map(|(e, _)| e).collect();
+    for source in dangling {
+        list.remove(source);
+        ecs.record(source, StructuralOp::Remove(std::any::type_name::<Relation<R>>()));
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// The type-erased hooks of a single kind of relation, as registered by `Ecs::register_relation()`.
+pub(crate) struct RelationHooks {
+    /// Removes all relations targeting one of the given (removed) entities.
+    pub(crate) cleanup  : fn(&Ecs, &EntityHashSet),
+    /// Returns all relations whose target no longer exists.
+    pub(crate) dangling : fn(&Ecs) -> Vec<(Entity, Entity)>,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines a relation of kind `R` from the entity that has this component to some target entity.
+/// 
+/// Relations are cleaned up automatically when the target entity is removed (and, like any component, when the source entity is removed). Use `Ecs::register_relation()` to register a new kind, and `Ecs::relate()` to create relations.
+/// 
+/// **Generic Types**
+///  * `R`: The kind of the relation, typically some empty marker struct (e.g., `struct Likes;`).
+pub struct Relation<R> {
+    /// The entity that is the target of this relation.
+    target : Entity,
+    /// Binds the kind to this relation.
+    _kind  : PhantomData<fn() -> R>,
+}
+
+impl<R> Relation<R> {
+    /// Constructor for the Relation.
+    /// 
+    /// **Arguments**
+    ///  * `target`: The entity that is the target of the relation.
+    #[inline]
+    pub(crate) fn new(target: Entity) -> Self {
+        Self {
+            target,
+            _kind : PhantomData,
+        }
+    }
+
+
+
+    /// Returns the target of this relation.
+    #[inline]
+    pub fn target(&self) -> Entity { self.target }
+}
+
+impl<R> Component for Relation<R> {}
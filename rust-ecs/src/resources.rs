@@ -0,0 +1,171 @@
+//  RESOURCES.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:49:24
+//  Last edited:
+//    16 Oct 2026, 00:48:39
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines resources, which are singleton values stored in the Ecs,
+//!   together with change detection for them.
+// 
+
+use std::any::Any;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use parking_lot::{RwLockReadGuard, RwLockWriteGuard};
+
+
+/***** AUXILLARY *****/
+/// Stores a single resource in the Ecs.
+pub(crate) struct ResourceData {
+    /// The value of the resource.
+    pub(crate) value   : Box<dyn Any>,
+    /// The change tick at which the resource was inserted.
+    pub(crate) added   : u64,
+    /// The change tick at which the resource was last mutably accessed.
+    pub(crate) changed : u64,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Provides immuteable access to a resource, including its change ticks.
+/// 
+/// The resource remains locked for reading while this guard lives.
+/// 
+/// **Generic Types**
+///  * `T`: The type of the resource.
+pub struct Res<'a, T> {
+    /// The (locked) resource data.
+    data  : RwLockReadGuard<'a, ResourceData>,
+    /// The current change tick of the Ecs.
+    tick  : u64,
+    /// Binds the resource type to this guard.
+    _type : PhantomData<&'a T>,
+}
+
+impl<'a, T: 'static> Res<'a, T> {
+    /// Constructor for the Res.
+    /// 
+    /// **Arguments**
+    ///  * `data`: The (locked) resource data. Must contain a value of type `T`.
+    ///  * `tick`: The current change tick of the Ecs.
+    #[inline]
+    pub(crate) fn new(data: RwLockReadGuard<'a, ResourceData>, tick: u64) -> Self {
+        Self {
+            data,
+            tick,
+            _type : PhantomData,
+        }
+    }
+
+
+
+    /// Returns whether the resource was inserted during the current change tick.
+    #[inline]
+    pub fn is_added(&self) -> bool { self.data.added >= self.tick }
+
+    /// Returns whether the resource was mutably accessed (or inserted) during the current change tick.
+    #[inline]
+    pub fn is_changed(&self) -> bool { self.data.changed >= self.tick }
+
+    /// Returns whether the resource was mutably accessed (or inserted) during or after the given change tick.
+    /// 
+    /// This is useful for consumers that do not run every tick, and thus remember the tick at which they last looked (see `Ecs::change_tick()`). Because a change tick spans a whole frame, changes made later in the tick at which the consumer looked are reported as well (as are changes made earlier in that tick, which may thus be reported twice).
+    /// 
+    /// **Arguments**
+    ///  * `tick`: The change tick to compare with.
+    #[inline]
+    pub fn is_changed_since(&self, tick: u64) -> bool { self.data.changed >= tick }
+
+    /// Returns the change tick at which the resource was last mutably accessed (or inserted).
+    #[inline]
+    pub fn last_changed(&self) -> u64 { self.data.changed }
+}
+
+impl<'a, T: 'static> Deref for Res<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.data.value.downcast_ref::<T>().expect("Could not downcast resource to its own type")
+    }
+}
+
+
+
+/// Provides muteable access to a resource, including its change ticks.
+/// 
+/// The resource is only marked as changed when it is actually mutably dereferenced. It remains locked for writing while this guard lives.
+/// 
+/// **Generic Types**
+///  * `T`: The type of the resource.
+pub struct ResMut<'a, T> {
+    /// The (locked) resource data.
+    data  : RwLockWriteGuard<'a, ResourceData>,
+    /// The current change tick of the Ecs.
+    tick  : u64,
+    /// Binds the resource type to this guard.
+    _type : PhantomData<&'a mut T>,
+}
+
+impl<'a, T: 'static> ResMut<'a, T> {
+    /// Constructor for the ResMut.
+    /// 
+    /// **Arguments**
+    ///  * `data`: The (locked) resource data. Must contain a value of type `T`.
+    ///  * `tick`: The current change tick of the Ecs.
+    #[inline]
+    pub(crate) fn new(data: RwLockWriteGuard<'a, ResourceData>, tick: u64) -> Self {
+        Self {
+            data,
+            tick,
+            _type : PhantomData,
+        }
+    }
+
+
+
+    /// Returns whether the resource was inserted during the current change tick.
+    #[inline]
+    pub fn is_added(&self) -> bool { self.data.added >= self.tick }
+
+    /// Returns whether the resource was mutably accessed (or inserted) during the current change tick.
+    #[inline]
+    pub fn is_changed(&self) -> bool { self.data.changed >= self.tick }
+
+    /// Returns whether the resource was mutably accessed (or inserted) during or after the given change tick. See `Res::is_changed_since()`.
+    /// 
+    /// **Arguments**
+    ///  * `tick`: The change tick to compare with.
+    #[inline]
+    pub fn is_changed_since(&self, tick: u64) -> bool { self.data.changed >= tick }
+
+    /// Returns the change tick at which the resource was last mutably accessed (or inserted).
+    #[inline]
+    pub fn last_changed(&self) -> u64 { self.data.changed }
+}
+
+impl<'a, T: 'static> Deref for ResMut<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.data.value.downcast_ref::<T>().expect("Could not downcast resource to its own type")
+    }
+}
+
+impl<'a, T: 'static> DerefMut for ResMut<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.data.changed = self.tick;
+        self.data.value.downcast_mut::<T>().expect("Could not downcast resource to its own type")
+    }
+}
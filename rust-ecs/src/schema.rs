@@ -0,0 +1,199 @@
+//  SCHEMA.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:01:36
+//  Last edited:
+//    16 Oct 2026, 00:34:05
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines the ComponentSchema, which describes the registered
+//!   component types of an Ecs for external tools.
+// 
+
+use std::fmt::Write as _;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::spec::Component;
+
+
+/***** CONSTANTS *****/
+/// The version of the schema format, which is increased whenever its layout changes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Appends the given string to the given JSON output as a (quoted and escaped) JSON string.
+/// 
+/// **Arguments**
+///  * `result`: The JSON output to append to.
+///  * `value`: The string to append.
+fn push_json_string(result: &mut String, value: &str) {
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"'  => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if c.is_control() => { write!(result, "\\u{:04x}", c as u32).unwrap(); },
+            c    => result.push(c),
+        }
+    }
+    result.push('"');
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines Components that describe themselves for external tools (e.g., to generate bindings), see `Ecs::register_description()`.
+/// 
+/// Without it, the schema only knows the name of a component type as given by `std::any::type_name()`, which is not stable across compiler versions, and nothing about its fields.
+pub trait Describe: Component {
+    /// A name for the component type that is stable across compiler versions (e.g., `"Health"`).
+    const NAME: &'static str;
+
+    /// Describes the fields of the component type.
+    /// 
+    /// **Returns**  
+    /// The name and type of every field, in declaration order. Tuple structs should use their field indices as names (e.g., `"0"`).
+    fn fields() -> Vec<FieldInfo>;
+}
+
+
+
+/// Describes a single field of a component type, see `Describe`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct FieldInfo {
+    /// The name of the field.
+    pub name : String,
+    /// The type of the field, as it should be shown to external tools (e.g., `"u32"` or `"Vec<f32>"`).
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty   : String,
+}
+
+impl FieldInfo {
+    /// Constructor for the FieldInfo.
+    /// 
+    /// **Arguments**
+    ///  * `name`: The name of the field.
+    ///  * `ty`: The type of the field.
+    #[inline]
+    pub fn new(name: impl Into<String>, ty: impl Into<String>) -> Self {
+        Self {
+            name : name.into(),
+            ty   : ty.into(),
+        }
+    }
+}
+
+
+
+/// Describes the stable name and fields of a component type, as provided by its `Describe` implementation.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ComponentDescription {
+    /// The stable name of the component type.
+    pub name   : String,
+    /// The fields of the component type, in declaration order.
+    pub fields : Vec<FieldInfo>,
+}
+
+impl ComponentDescription {
+    /// Constructor for the ComponentDescription from a `Describe` implementation.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to describe.
+    #[inline]
+    pub(crate) fn of<T: Describe>() -> Self {
+        Self {
+            name   : T::NAME.into(),
+            fields : T::fields(),
+        }
+    }
+}
+
+
+
+/// Describes a single registered component type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ComponentInfo {
+    /// The (full) name of the component type, as given by `std::any::type_name()`. Note that this is not stable across compiler versions; use the name in the `description` where possible.
+    pub name        : String,
+    /// The size (in bytes) of a single component.
+    pub size        : usize,
+    /// The alignment (in bytes) of a single component.
+    pub align       : usize,
+    /// The stable name and fields of the component type, if it has been described (see `Describe`).
+    pub description : Option<ComponentDescription>,
+}
+
+
+
+/// Describes all component types registered in an Ecs, as returned by `Ecs::schema()`.
+/// 
+/// It can be exported to JSON (or, with the `serde` feature, any other format) so external tools can validate against the game's data model.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ComponentSchema {
+    /// The version of the schema format (see `SCHEMA_VERSION`).
+    pub version    : u32,
+    /// The registered component types, ordered by name.
+    pub components : Vec<ComponentInfo>,
+}
+
+impl ComponentSchema {
+    /// Returns the component type with the given name.
+    /// 
+    /// **Arguments**
+    ///  * `name`: The (full) name of the component type, or its stable name if it has been described (see `Describe`).
+    /// 
+    /// **Returns**  
+    /// The ComponentInfo of that type, or None if it has not been registered.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&ComponentInfo> {
+        self.components.iter().find(|c| c.name == name || c.description.as_ref().map(|d| d.name == name).unwrap_or(false))
+    }
+
+    /// Serializes the schema to JSON.
+    /// 
+    /// **Returns**  
+    /// The schema as a (compact) JSON string.
+    pub fn to_json(&self) -> String {
+        let mut result: String = format!("{{\"version\":{},\"components\":[", self.version);
+        for (i, component) in self.components.iter().enumerate() {
+            if i > 0 { result.push(','); }
+            result.push_str("{\"name\":");
+            push_json_string(&mut result, &component.name);
+            write!(result, ",\"size\":{},\"align\":{}", component.size, component.align).unwrap();
+
+            // Add the description, if any
+            if let Some(description) = &component.description {
+                result.push_str(",\"stable_name\":");
+                push_json_string(&mut result, &description.name);
+                result.push_str(",\"fields\":[");
+                for (j, field) in description.fields.iter().enumerate() {
+                    if j > 0 { result.push(','); }
+                    result.push_str("{\"name\":");
+                    push_json_string(&mut result, &field.name);
+                    result.push_str(",\"type\":");
+                    push_json_string(&mut result, &field.ty);
+                    result.push('}');
+                }
+                result.push(']');
+            }
+            result.push('}');
+        }
+        result.push_str("]}");
+        result
+    }
+}
@@ -0,0 +1,181 @@
+//  SNAPSHOT.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:00:57
+//  Last edited:
+//    16 Oct 2026, 00:50:59
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines QuerySnapshots, which are owned copies of the components of
+//!   all entities that match a query, and may be used to temporarily
+//!   rewind the Ecs to them.
+// 
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard};
+
+use crate::spec::{Component, Entity};
+use crate::bundle::has_duplicates;
+use crate::list::ComponentList;
+use crate::system::Ecs;
+
+
+/***** HELPER MACROS *****/
+/// Implements the Snapshot trait for a tuple of the given generic types.
+macro_rules! impl_snapshot {
+    ($first:ident $fi:tt $(, $t:ident $ti:tt)*) => {
+        impl<$first: 'static + Component + Clone $(, $t: 'static + Component + Clone)*> Snapshot for ($first, $($t,)*) {
+            #[allow(non_snake_case)]
+            fn capture(ecs: &Ecs) -> Vec<(Entity, Self)> {
+                // Locking the same list twice may deadlock
+                if has_duplicates(&[ComponentList::<$first>::id() $(, ComponentList::<$t>::id())*]) { return vec![]; }
+
+                // Lock all lists first
+                let $first: MappedRwLockReadGuard<ComponentList<$first>> = ecs.list_component::<$first>();
+                $(let $t: MappedRwLockReadGuard<ComponentList<$t>> = ecs.list_component::<$t>();)*
+
+                // Copy the components of every entity that has all of them
+                let mut result: Vec<(Entity, Self)> = $first.iter_entities().filter_map(|(entity, first)| {
+                    Some((entity, (first.clone(), $($t.get(entity)?.clone(),)*)))
+                }).collect();
+                result.sort_by_key(|(entity, _)| *entity);
+                result
+            }
+
+            #[allow(non_snake_case)]
+            fn swap(ecs: &Ecs, entries: &mut [(Entity, Self)], mask: Option<&[u8]>) -> Vec<u8> {
+                // Locking the same list twice would deadlock
+                if has_duplicates(&[ComponentList::<$first>::id() $(, ComponentList::<$t>::id())*]) { return vec![ 0; entries.len() ]; }
+
+                // Lock all lists first
+                let mut $first: MappedRwLockWriteGuard<ComponentList<$first>> = ecs.list_component_mut::<$first>();
+                $(let mut $t: MappedRwLockWriteGuard<ComponentList<$t>> = ecs.list_component_mut::<$t>();)*
+
+                // Swap every component that (still) exists, and remember which ones we swapped
+                let mut swapped: Vec<u8> = Vec::with_capacity(entries.len());
+                for (i, (entity, values)) in entries.iter_mut().enumerate() {
+                    let allowed: u8 = mask.map(|mask| mask[i]).unwrap_or(u8::MAX);
+                    let mut bits: u8 = 0;
+                    if allowed & (1 << $fi) != 0 {
+                        if let Some(component) = $first.get_mut(*entity) { std::mem::swap(component, &mut values.$fi); bits |= 1 << $fi; }
+                    }
+                    $(if allowed & (1 << $ti) != 0 {
+                        if let Some(component) = $t.get_mut(*entity) { std::mem::swap(component, &mut values.$ti); bits |= 1 << $ti; }
+                    })*
+                    swapped.push(bits);
+                }
+                swapped
+            }
+        }
+    };
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines a set of (cloneable) Component types that may be captured in a QuerySnapshot.
+/// 
+/// It is implemented for tuples of up to eight Components. Note that every Component type may only occur once in the tuple; tuples that violate this capture and swap nothing.
+pub trait Snapshot: Sized {
+    /// Copies the components of all entities that have all of the components in this set.
+    /// 
+    /// Every component list is locked only once.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to copy the components from.
+    /// 
+    /// **Returns**  
+    /// The entities and their copied components, ordered by entity. Is empty if a Component type occurs more than once in this set.
+    fn capture(ecs: &Ecs) -> Vec<(Entity, Self)>;
+
+    /// Swaps the given components with the ones currently in the Ecs.
+    /// 
+    /// Components that an entity does not have (anymore) are skipped. Calling this twice with the mask returned by the first call swaps back exactly the components that were swapped in.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to swap the components in.
+    ///  * `entries`: The entities and the components to swap with theirs.
+    ///  * `mask`: If given, only swaps the components whose bit (by position in the tuple) is set in the mask of their entry.
+    /// 
+    /// **Returns**  
+    /// A mask for every entry with the bits of the components that have been swapped. Nothing is swapped if a Component type occurs more than once in this set.
+    fn swap(ecs: &Ecs, entries: &mut [(Entity, Self)], mask: Option<&[u8]>) -> Vec<u8>;
+}
+
+impl_snapshot!(A 0);
+impl_snapshot!(A 0, B 1);
+impl_snapshot!(A 0, B 1, C 2);
+impl_snapshot!(A 0, B 1, C 2, D 3);
+impl_snapshot!(A 0, B 1, C 2, D 3, E 4);
+impl_snapshot!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_snapshot!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_snapshot!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+
+
+/// An owned copy of the components of all entities that match a query, as captured by `Ecs::snapshot()`.
+/// 
+/// Because it does not borrow the Ecs, it may be kept around (e.g., to inspect a frame later) or (with the `serde` feature) serialized.
+/// 
+/// **Generic Types**
+///  * `Q`: The tuple of Component types that has been captured.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct QuerySnapshot<Q> {
+    /// The entities and their components, ordered by entity.
+    entries : Vec<(Entity, Q)>,
+}
+
+impl<Q: Snapshot> QuerySnapshot<Q> {
+    /// Captures a new QuerySnapshot.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to capture the components of.
+    #[inline]
+    pub(crate) fn capture(ecs: &Ecs) -> Self {
+        Self {
+            entries : Q::capture(ecs),
+        }
+    }
+}
+
+impl<Q> QuerySnapshot<Q> {
+    /// Returns the captured components of the given entity.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to get the components of.
+    /// 
+    /// **Returns**  
+    /// The components, or None if the entity did not match the query when the snapshot was taken.
+    #[inline]
+    pub fn get(&self, entity: Entity) -> Option<&Q> {
+        self.entries.binary_search_by_key(&entity, |(e, _)| *e).ok().map(|i| &self.entries[i].1)
+    }
+
+    /// Returns an iterator over the captured entities and their components, ordered by entity.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &Q)> {
+        self.entries.iter().map(|(e, q)| (*e, q))
+    }
+
+    /// Returns the captured entities, ordered by entity.
+    #[inline]
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entries.iter().map(|(e, _)| *e)
+    }
+
+    /// Returns the number of captured entities.
+    #[inline]
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// Returns whether any entities have been captured.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+}
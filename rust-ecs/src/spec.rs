@@ -4,7 +4,7 @@
 //  Created:
 //    26 Mar 2022, 10:32:55
 //  Last edited:
-//    06 Aug 2022, 16:20:39
+//    16 Oct 2026, 00:48:07
 //  Auto updated?
 //    Yes
 // 
@@ -12,40 +12,251 @@
 //!   Defines the interfaces to the library: common types, structs, etc.
 // 
 
+use std::alloc::Layout;
 use std::any::{Any, TypeId};
-use std::fmt::Debug;
-use std::hash::{Hash, Hasher};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::num::NonZeroU64;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::{Mutex, MutexGuard};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::errors::EcsError;
 
 
 /***** CUSTOM TYPES *****/
 /// Defines the type used for all entitites.
+/// 
+/// Entity IDs are never zero, which means that an `Option<Entity>` is just as large as an `Entity`.
+/// 
+/// # Formatting
+/// Entities are displayed, parsed and (with the `serde` feature) serialized as their decimal ID (e.g., `42`). Because IDs are never reused by an Ecs, there is no separate generation to encode.
 #[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
-pub struct Entity(u64);
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Entity(NonZeroU64);
+
+// Make sure the niche optimization actually kicks in
+const _: () = assert!(std::mem::size_of::<Option<Entity>>() == std::mem::size_of::<Entity>());
+
+impl Entity {
+    /// An Entity that is never handed out by an Ecs. Useful to initialize entity fields that will be overwritten later.
+    pub const PLACEHOLDER: Entity = Entity(match NonZeroU64::new(u64::MAX) { Some(id) => id, None => unreachable!() });
+
+
+    /// Constructor for an Entity from a raw ID that is known to be non-zero.
+    /// 
+    /// **Arguments**
+    ///  * `id`: The ID of the new Entity.
+    /// 
+    /// **Returns**  
+    /// A new Entity with the given ID.
+    /// 
+    /// **Panics**  
+    /// This function panics if the given ID is zero.
+    #[inline]
+    pub(crate) fn from_id(id: u64) -> Self {
+        Self(NonZeroU64::new(id).expect("Entity ID cannot be zero"))
+    }
+
+
+    /// Returns a WeakEntity for this Entity, which can be stored long-term and resolved later.
+    #[inline]
+    pub fn downgrade(self) -> WeakEntity { WeakEntity(self) }
+}
 
 impl Hash for Entity {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.hash(state);
+        state.write_u64(self.0.get());
+    }
+}
+
+impl Display for Entity {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        write!(f, "{}", self.0)
     }
 }
 
-impl From<u64> for Entity {
+impl FromStr for Entity {
+    type Err = EcsError;
+
     #[inline]
-    fn from(value: u64) -> Self {
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match u64::from_str(value) {
+            Ok(id)   => Self::try_from(id),
+            Err(err) => Err(EcsError::EntityParseError{ raw: value.into(), err }),
+        }
+    }
+}
+
+impl TryFrom<u64> for Entity {
+    type Error = EcsError;
+
+    #[inline]
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        match NonZeroU64::new(value) {
+            Some(id) => Ok(Self(id)),
+            None     => Err(EcsError::NullEntity),
+        }
+    }
+}
+
+impl From<NonZeroU64> for Entity {
+    #[inline]
+    fn from(value: NonZeroU64) -> Self {
         Self(value)
     }
 }
 
 impl From<Entity> for u64 {
+    #[inline]
+    fn from(value: Entity) -> Self {
+        value.0.get()
+    }
+}
+
+impl From<Entity> for NonZeroU64 {
     #[inline]
     fn from(value: Entity) -> Self {
         value.0
     }
 }
 
+/// Defines a long-lived reference to an Entity that may have been removed in the meantime.
+/// 
+/// It has to be resolved using `Ecs::resolve()` before use, which returns None if the Entity no longer exists. Because entity IDs are never reused, a WeakEntity can never resolve to a different entity than the one it was created from.
+#[derive(Debug, Copy, Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct WeakEntity(Entity);
+
+impl WeakEntity {
+    /// Returns the Entity this WeakEntity refers to, without checking if it still exists.
+    #[inline]
+    pub fn entity_unchecked(&self) -> Entity { self.0 }
+}
+
+impl From<Entity> for WeakEntity {
+    #[inline]
+    fn from(value: Entity) -> Self {
+        Self(value)
+    }
+}
+
+/// Reserves entity IDs for an Ecs from any thread, see `Ecs::entity_reserver()`.
+/// 
+/// The Ecs itself cannot be shared between threads, but this handle can: it only shares the counter of the next entity ID. Reserved entities become alive at the next call to `Ecs::flush_entities()` (or `Ecs::add_entity()`, which flushes implicitly).
+#[derive(Clone, Debug)]
+pub struct EntityReserver {
+    /// The next entity ID to hand out, shared with the Ecs.
+    next     : Arc<AtomicU64>,
+    /// The IDs reserved for CommandBuffers, which are skipped when flushing (they become alive when their buffer is applied instead). Shared with the Ecs.
+    deferred : Arc<Mutex<EntityHashSet>>,
+}
+
+impl EntityReserver {
+    /// Constructor for the EntityReserver.
+    /// 
+    /// **Arguments**
+    ///  * `next`: The counter of the next entity ID to hand out, shared with the Ecs.
+    ///  * `deferred`: The set of IDs reserved for CommandBuffers, shared with the Ecs.
+    #[inline]
+    pub(crate) fn new(next: Arc<AtomicU64>, deferred: Arc<Mutex<EntityHashSet>>) -> Self {
+        Self {
+            next,
+            deferred,
+        }
+    }
+
+
+
+    /// Reserves a new entity ID.
+    /// 
+    /// **Returns**  
+    /// The identifier of the reserved entity, as an Entity.
+    #[inline]
+    pub fn reserve(&self) -> Entity {
+        Entity::from_id(self.next.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Reserves a new entity ID that is not made alive by flushing, but only by `Ecs::spawn_deferred()` (i.e., when the CommandBuffer that spawned it is applied).
+    /// 
+    /// **Returns**  
+    /// The identifier of the reserved entity, as an Entity.
+    pub(crate) fn reserve_deferred(&self) -> Entity {
+        // Keep the set locked while reserving, so a concurrent flush never sees the ID before it is marked
+        let mut deferred: MutexGuard<EntityHashSet> = self.deferred.lock();
+        let entity: Entity = self.reserve();
+        deferred.insert(entity);
+        entity
+    }
+}
+
+/// Defines a fast Hasher for Entities.
+/// 
+/// Because entity IDs are already (sequential) unique integers, there is no need for a cryptographically sound hash like SipHash. Instead, the ID is simply multiplied with a large odd constant to spread the sequential IDs over all bits of the hash.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EntityHasher(u64);
+
+impl Hasher for EntityHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        // Fallback for non-u64 input; shouldn't occur for Entities
+        for b in bytes {
+            self.0 = (self.0.rotate_left(8) ^ (*b as u64)).wrapping_mul(0x9E3779B97F4A7C15);
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, value: u64) {
+        self.0 = value.wrapping_mul(0x9E3779B97F4A7C15);
+    }
+}
+
+/// A HashMap keyed by Entities that uses the fast EntityHasher.
+pub type EntityHashMap<V> = HashMap<Entity, V, BuildHasherDefault<EntityHasher>>;
+
+/// A HashSet of Entities that uses the fast EntityHasher.
+pub type EntityHashSet = HashSet<Entity, BuildHasherDefault<EntityHasher>>;
+
+
+
 /// Defines the base Component trait.
 pub trait Component {}
 
+/// Defines Components that own (heavy) payloads which may be pooled and reused, see `Ecs::register_pooled()`.
+pub trait Poolable: Component + Default {
+    /// Resets the component before it is reused, ideally without freeing its allocations (e.g., by clearing its vectors).
+    fn reset(&mut self);
+}
+
+/// Defines Components that are plain old data, and may thus be viewed as raw bytes (e.g., to copy them into GPU staging buffers), see `ComponentList::as_bytes()`.
+/// 
+/// # Safety
+/// Only implement this for types that are `#[repr(C)]` (or `#[repr(transparent)]`), contain no padding bytes, and consist only of fields that are themselves plain old data (integers, floats or arrays or Pod structs of them). In particular, no references, pointers, `bool`s or enums.
+pub unsafe trait Pod: Component + Copy + 'static {}
+
+/// Defines Components that can check their own values (e.g., for NaNs or negative health), see `Ecs::register_validator()`.
+pub trait Validate: Component {
+    /// Checks whether this component has a valid value.
+    /// 
+    /// **Errors**  
+    /// This function errors with a human-readable reason if the value is invalid.
+    fn validate(&self) -> Result<(), String>;
+}
+
 
 
 /// Defines a type-agnostic base for a ComponentList.
@@ -64,6 +275,9 @@ pub trait ComponentListBase {
     /// Returns the name of the ComponentList's type.
     fn type_name(&self) -> &'static str;
 
+    /// Returns the memory layout of a single component in this list.
+    fn layout(&self) -> Layout;
+
 
 
     /// Get the index from an entity.  
@@ -86,6 +300,27 @@ pub trait ComponentListBase {
     /// The entity that resides at the given index if the index is in range, or None otherwise.
     fn get_entity(&self, index: usize) -> Option<Entity>;
 
+    /// Returns the component of the given entity as a type-erased reference.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to get the component of.
+    /// 
+    /// **Returns**  
+    /// The component (which may be downcasted to its concrete type) if the entity has one in this list, or None otherwise.
+    fn get_any(&self, entity: Entity) -> Option<&dyn Any>;
+
+    /// Returns the number of components in the list.
+    fn len(&self) -> usize;
+
+    /// Returns whether the list has any components.
+    fn is_empty(&self) -> bool;
+
+    /// Checks whether the internal mappings of the list are consistent.
+    /// 
+    /// **Returns**  
+    /// True if the mappings are consistent, or false if the list is corrupted.
+    fn is_consistent(&self) -> bool;
+
 
 
     /// Deletes the given entity if it existed from the internal list.
@@ -93,4 +328,7 @@ pub trait ComponentListBase {
     /// **Arguments**
     ///  * `entity`: The Entity to remove the data of.
     fn delete(&mut self, entity: Entity);
+
+    /// Deletes all components from the internal list.
+    fn clear(&mut self);
 }
@@ -0,0 +1,169 @@
+//  SPLIT.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:11:46
+//  Last edited:
+//    16 Oct 2026, 00:11:46
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines access sets for `Ecs::split()`, which locks several component
+//!   lists at once (in a canonical order) and hands out plain references
+//!   to them.
+// 
+
+use std::any::TypeId;
+use std::marker::PhantomData;
+
+use parking_lot::{RwLockReadGuard, RwLockWriteGuard};
+
+use crate::spec::{Component, ComponentListBase};
+use crate::list::ComponentList;
+
+
+/***** HELPER MACROS *****/
+/// Implements the AccessSet trait for a tuple of the given generic types.
+macro_rules! impl_access_set {
+    ($($t:ident),+) => {
+        impl<$($t: Access),+> AccessSet for ($($t,)+) {
+            type Items<'a> = ($($t::Item<'a>,)+);
+
+            #[inline]
+            fn accesses() -> Vec<(TypeId, &'static str, bool)> {
+                vec![$(($t::id(), $t::name(), $t::is_write()),)+]
+            }
+
+            #[inline]
+            fn fetch<'a>(locks: &'a mut [LockedList]) -> Self::Items<'a> {
+                let mut locks = locks.iter_mut();
+                ($($t::fetch(locks.next().expect("Not enough locked lists for access set")),)+)
+            }
+        }
+    };
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A component list that has been locked for either reading or writing.
+pub enum LockedList<'a> {
+    /// The list is locked for reading.
+    Read(RwLockReadGuard<'a, Box<dyn ComponentListBase>>),
+    /// The list is locked for writing.
+    Write(RwLockWriteGuard<'a, Box<dyn ComponentListBase>>),
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Requests immuteable access to the list of the given Component type in `Ecs::split()`.
+/// 
+/// **Generic Types**
+///  * `T`: The Component type to read.
+pub struct Read<T>(PhantomData<fn() -> T>);
+
+/// Requests muteable access to the list of the given Component type in `Ecs::split()`.
+/// 
+/// **Generic Types**
+///  * `T`: The Component type to write.
+pub struct Write<T>(PhantomData<fn() -> T>);
+
+
+
+/// Defines a single list access (i.e., `Read<T>` or `Write<T>`) in an AccessSet.
+pub trait Access {
+    /// The reference to the list that is handed out.
+    type Item<'a>;
+
+    /// Returns the TypeId of the Component type that is accessed.
+    fn id() -> TypeId;
+
+    /// Returns the name of the Component type that is accessed.
+    fn name() -> &'static str;
+
+    /// Returns whether the list must be locked for writing.
+    fn is_write() -> bool;
+
+    /// Returns the typed reference to the list.
+    /// 
+    /// **Arguments**
+    ///  * `lock`: The locked list, which is of the type returned by `Access::id()` and locked for writing if `Access::is_write()` says so.
+    fn fetch<'a>(lock: &'a mut LockedList) -> Self::Item<'a>;
+}
+
+impl<T: 'static + Component> Access for Read<T> {
+    type Item<'a> = &'a ComponentList<T>;
+
+    #[inline]
+    fn id() -> TypeId { TypeId::of::<T>() }
+
+    #[inline]
+    fn name() -> &'static str { std::any::type_name::<T>() }
+
+    #[inline]
+    fn is_write() -> bool { false }
+
+    #[inline]
+    fn fetch<'a>(lock: &'a mut LockedList) -> Self::Item<'a> {
+        let list: &dyn ComponentListBase = match lock {
+            LockedList::Read(guard)  => &***guard,
+            LockedList::Write(guard) => &***guard,
+        };
+        list.as_any().downcast_ref::<ComponentList<T>>().expect("Could not downcast component list to its own type")
+    }
+}
+
+impl<T: 'static + Component> Access for Write<T> {
+    type Item<'a> = &'a mut ComponentList<T>;
+
+    #[inline]
+    fn id() -> TypeId { TypeId::of::<T>() }
+
+    #[inline]
+    fn name() -> &'static str { std::any::type_name::<T>() }
+
+    #[inline]
+    fn is_write() -> bool { true }
+
+    #[inline]
+    fn fetch<'a>(lock: &'a mut LockedList) -> Self::Item<'a> {
+        match lock {
+            LockedList::Write(guard) => guard.as_any_mut().downcast_mut::<ComponentList<T>>().expect("Could not downcast component list to its own type"),
+            LockedList::Read(_)      => panic!("Component list of type '{}' is not locked for writing", std::any::type_name::<T>()),
+        }
+    }
+}
+
+
+
+/// Defines a set of list accesses that may be used in `Ecs::split()`, e.g. `(Write<Position>, Read<Velocity>)`.
+/// 
+/// It is implemented for tuples of up to eight `Read<T>`s and `Write<T>`s.
+pub trait AccessSet {
+    /// The tuple of list references that is handed out.
+    type Items<'a>;
+
+    /// Returns the accessed Component types, in order, as (TypeId, name, is_write) triples.
+    fn accesses() -> Vec<(TypeId, &'static str, bool)>;
+
+    /// Returns the typed references to the lists.
+    /// 
+    /// **Arguments**
+    ///  * `locks`: The locked lists, in the same order as returned by `AccessSet::accesses()`.
+    fn fetch<'a>(locks: &'a mut [LockedList]) -> Self::Items<'a>;
+}
+
+impl_access_set!(A);
+impl_access_set!(A, B);
+impl_access_set!(A, B, C);
+impl_access_set!(A, B, C, D);
+impl_access_set!(A, B, C, D, E);
+impl_access_set!(A, B, C, D, E, F);
+impl_access_set!(A, B, C, D, E, F, G);
+impl_access_set!(A, B, C, D, E, F, G, H);
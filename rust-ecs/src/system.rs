@@ -4,7 +4,7 @@
 //  Created:
 //    26 Mar 2022, 10:31:26
 //  Last edited:
-//    06 Aug 2022, 16:23:34
+//    16 Oct 2026, 00:52:58
 //  Auto updated?
 //    Yes
 // 
@@ -12,17 +12,1318 @@
 //!   Implements the base system itself.
 // 
 
-use std::any::TypeId;
+use std::any::{Any, TypeId};
 use std::cell::{RefCell, RefMut};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::debug;
 use crate::{to_component_list, to_component_list_mut};
-use crate::spec::{Component, ComponentListBase, Entity};
+pub use crate::errors::EcsError as Error;
+use crate::bundle::Bundle;
+use crate::spec::{Component, ComponentListBase, Entity, EntityHashSet, EntityReserver, Poolable, Validate, WeakEntity};
 use crate::list::ComponentList;
+use crate::relation::{cleanup_relations, dangling_relations, Relation, RelationHooks};
+use crate::hierarchy::{Ancestors, ChildOf, Descendants, TraversalOrder};
+use crate::oneshot::{BoxedSystem, SystemId};
+use crate::commands::CommandBuffer;
+use crate::resources::{Res, ResMut, ResourceData};
+use crate::previous::{store_previous, Previous};
+use crate::events::Events;
+use crate::fsm::{StateChanged, StateMachine, Transitions};
+use crate::snapshot::{QuerySnapshot, Snapshot};
+use crate::schema::{ComponentDescription, ComponentInfo, ComponentSchema, Describe, SCHEMA_VERSION};
+use crate::view::WorldView;
+use crate::split::{AccessSet, LockedList};
+use crate::extract::{Extract, EntityMapping, ExtractIds};
+use crate::checksum::{HashedComponents, StateHash};
+use crate::quota::{Quota, QuotaRejection, QuotaWarning};
+use crate::orphan::{OrphanedComponent, OrphanPolicy};
+use crate::journal::{Journal, StructuralOp};
+
+
+/***** UNIT TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::QueryBuilder;
+    use crate::schema::FieldInfo;
+
+    /// Simple component to test with
+    struct Health(u32);
+    impl Component for Health {}
+
+    /// Another simple component to test with
+    struct Player;
+    impl Component for Player {}
+
+    /// Tests reserving entities without the entity lock
+    #[test]
+    fn test_reserve_entity() {
+        let ecs = Ecs::new(16);
+        let ecs = ecs.borrow();
+
+        // Reserve some entities; they should not be alive yet
+        let reserved: Vec<Entity> = (0..4).map(|_| ecs.reserve_entity()).collect();
+        assert_eq!(reserved.iter().map(|e| u64::from(*e)).collect::<Vec<u64>>(), vec![ 1, 2, 3, 4 ]);
+        assert!(!ecs.remove_entity(reserved[0]));
+
+        // Adding a normal entity should flush the reserved ones
+        let entity = ecs.add_entity();
+        assert_eq!(u64::from(entity), 5);
+        assert!(ecs.remove_entity(reserved[0]));
+        assert!(ecs.remove_entity(entity));
+
+        // Or we can flush manually
+        let reserved = ecs.reserve_entity();
+        assert_eq!(u64::from(reserved), 6);
+        ecs.flush_entities();
+        assert!(ecs.remove_entity(reserved));
+
+        // Worker threads reserve through a shared handle
+        let reserver: EntityReserver = ecs.entity_reserver();
+        let mut reserved: Vec<Entity> = std::thread::scope(|s| {
+            let workers: Vec<_> = (0..4).map(|_| {
+                let reserver: EntityReserver = reserver.clone();
+                s.spawn(move || (0..8).map(|_| reserver.reserve()).collect::<Vec<Entity>>())
+            }).collect();
+            workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+        });
+        reserved.sort();
+        reserved.dedup();
+        assert_eq!(reserved.len(), 32);
+        assert!(reserved.iter().all(|e| !ecs.is_alive(*e)));
+        ecs.flush_entities();
+        assert!(reserved.iter().all(|e| ecs.is_alive(*e)));
+    }
+
+    /// Tests the niche optimization and placeholder of Entities
+    #[test]
+    fn test_entity_niche() {
+        assert_eq!(std::mem::size_of::<Option<Entity>>(), 8);
+        assert!(Entity::try_from(0).is_err());
+        assert_eq!(u64::from(Entity::try_from(42).unwrap()), 42);
+
+        // The placeholder is never handed out
+        let ecs = Ecs::new(16);
+        let ecs = ecs.borrow();
+        assert_ne!(ecs.add_entity(), Entity::PLACEHOLDER);
+        assert!(!ecs.remove_entity(Entity::PLACEHOLDER));
+    }
+
+    /// Tests resolving weak entities
+    #[test]
+    fn test_weak_entity() {
+        let ecs = Ecs::new(16);
+        let ecs = ecs.borrow();
+
+        let entity = ecs.add_entity();
+        let weak: WeakEntity = entity.downgrade();
+        assert_eq!(ecs.resolve(weak), Some(entity));
+        ecs.remove_entity(entity);
+        assert_eq!(ecs.resolve(weak), None);
+
+        // A new entity never takes its place
+        ecs.add_entity();
+        assert_eq!(ecs.resolve(weak), None);
+    }
+
+    /// Tests formatting and parsing Entities
+    #[test]
+    fn test_entity_string() {
+        let entity = Entity::try_from(42).unwrap();
+        assert_eq!(entity.to_string(), "42");
+        assert_eq!("42".parse::<Entity>().unwrap(), entity);
+        assert!("0".parse::<Entity>().is_err());
+        assert!("42v3".parse::<Entity>().is_err());
+    }
+
+    /// Tests bulk-removing entities
+    #[test]
+    fn test_despawn_where_clear() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register::<Player>(&ecs);
+        let ecs = ecs.borrow();
+
+        // Add some entities, some of which are dead
+        let entities: Vec<Entity> = (0..8).map(|i| {
+            let entity = ecs.add_entity();
+            ecs.add_component(entity, Health(i % 2));
+            entity
+        }).collect();
+        ecs.add_component(entities[0], Player);
+
+        // Remove the dead ones
+        assert_eq!(ecs.despawn_where(|e| ecs.get_component::<Health>(e).map(|h| h.0 == 0).unwrap_or(false)), 4);
+        assert_eq!(ecs.list_component::<Health>().iter().count(), 4);
+        assert!(ecs.list_component::<Health>().iter().all(|h| h.0 == 1));
+        assert_eq!(ecs.list_component::<Player>().iter().count(), 0);
+
+        // Clear the rest
+        ecs.clear();
+        assert_eq!(ecs.list_component::<Health>().iter().count(), 0);
+        assert!(!ecs.remove_entity(entities[1]));
+        assert!(ecs.add_component(ecs.add_entity(), Player));
+        assert_eq!(ecs.clear_component::<Player>(), 1);
+        assert!(ecs.list_component::<Player>().is_empty());
+    }
+
+    /// Tests removing multiple components at once
+    #[test]
+    fn test_remove_components() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register::<Player>(&ecs);
+        let ecs = ecs.borrow();
+
+        let entity = ecs.add_entity();
+        ecs.add_component(entity, Health(42));
+        let (health, player) = ecs.remove_components::<(Health, Player)>(entity);
+        assert_eq!(health.map(|h| h.0), Some(42));
+        assert!(player.is_none());
+        assert!(ecs.get_component::<Health>(entity).is_none());
+    }
+
+    /// Tests swapping components between entities
+    #[test]
+    fn test_swap_component() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        let ecs = ecs.borrow();
+
+        let a = ecs.add_entity();
+        let b = ecs.add_entity();
+        let c = ecs.add_entity();
+        ecs.add_component(a, Health(1));
+        ecs.add_component(b, Health(2));
+
+        // Swap both ways
+        assert!(ecs.swap_component::<Health>(a, b));
+        assert_eq!(ecs.get_component::<Health>(a).unwrap().0, 2);
+        assert_eq!(ecs.get_component::<Health>(b).unwrap().0, 1);
+
+        // Move to an entity without one
+        assert!(ecs.swap_component::<Health>(c, a));
+        assert!(ecs.get_component::<Health>(a).is_none());
+        assert_eq!(ecs.get_component::<Health>(c).unwrap().0, 2);
+
+        // Removal should still keep the mappings intact
+        ecs.remove_entity(b);
+        assert_eq!(ecs.get_component::<Health>(c).unwrap().0, 2);
+        assert!(!ecs.swap_component::<Health>(a, ecs.add_entity()));
+    }
+
+    /// Tests taking an entity as a bundle
+    #[test]
+    fn test_take() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register::<Player>(&ecs);
+        let ecs = ecs.borrow();
+
+        // Taking fails if not all components are there
+        let entity = ecs.add_entity();
+        ecs.add_component(entity, Health(42));
+        assert!(ecs.take::<(Health, Player)>(entity).is_none());
+        assert!(ecs.get_component::<Health>(entity).is_some());
+
+        // Or if a component type occurs twice (instead of deadlocking)
+        assert!(ecs.take::<(Health, Health)>(entity).is_none());
+        assert!(ecs.get_component::<Health>(entity).is_some());
+
+        // But succeeds otherwise, consuming the entity
+        ecs.add_component(entity, Player);
+        let (health,): (Health,) = ecs.take(entity).unwrap();
+        assert_eq!(health.0, 42);
+        assert!(!ecs.remove_entity(entity));
+        assert!(ecs.list_component::<Player>().is_empty());
+    }
+
+    /// Tests relations between entities
+    #[test]
+    fn test_relations() {
+        struct Likes;
+
+        let ecs = Ecs::new(16);
+        Ecs::register_relation::<Likes>(&ecs);
+        let ecs = ecs.borrow();
+
+        let a = ecs.add_entity();
+        let b = ecs.add_entity();
+        let c = ecs.add_entity();
+        assert!(ecs.relate::<Likes>(a, c));
+        assert!(ecs.relate::<Likes>(b, c));
+        assert!(ecs.relate::<Likes>(c, a));
+        assert_eq!(ecs.related::<Likes>(a), Some(c));
+        let mut targeting: Vec<Entity> = ecs.targeting::<Likes>(c);
+        targeting.sort();
+        assert_eq!(targeting, vec![ a, b ]);
+
+        // Removing the target should remove the relations to it
+        ecs.remove_entity(c);
+        assert_eq!(ecs.related::<Likes>(a), None);
+        assert_eq!(ecs.related::<Likes>(b), None);
+        assert!(ecs.list_component::<Relation<Likes>>().is_empty());
+        assert!(!ecs.relate::<Likes>(a, c));
+
+        // The same for bulk removal
+        assert!(ecs.relate::<Likes>(a, b));
+        assert_eq!(ecs.despawn_where(|e| e == b), 1);
+        assert_eq!(ecs.unrelate::<Likes>(a), None);
+        assert!(ecs.check_integrity().is_empty());
+    }
+
+    /// Tests traversing the hierarchy
+    #[test]
+    fn test_hierarchy() {
+        let ecs = Ecs::new(16);
+        Ecs::register_hierarchy(&ecs);
+        let ecs = ecs.borrow();
+
+        // Build a small tree:
+        //       root
+        //      /    \
+        //     a      b
+        //    / \
+        //   c   d
+        let root = ecs.add_entity();
+        let a = ecs.add_entity();
+        let b = ecs.add_entity();
+        let c = ecs.add_entity();
+        let d = ecs.add_entity();
+        assert!(ecs.set_parent(a, root));
+        assert!(ecs.set_parent(b, root));
+        assert!(ecs.set_parent(c, a));
+        assert!(ecs.set_parent(d, a));
+
+        // Cycles are not allowed, not even when relating directly
+        assert!(!ecs.set_parent(root, d));
+        assert!(!ecs.set_parent(a, a));
+        assert!(!ecs.relate::<ChildOf>(root, c));
+
+        // Check the traversals
+        assert_eq!(ecs.ancestors(d).collect::<Vec<Entity>>(), vec![ a, root ]);
+        assert_eq!(ecs.descendants(root, TraversalOrder::DepthFirst).collect::<Vec<Entity>>(), vec![ a, c, d, b ]);
+        assert_eq!(ecs.descendants(root, TraversalOrder::BreadthFirst).collect::<Vec<Entity>>(), vec![ a, b, c, d ]);
+        assert_eq!(ecs.siblings(c).collect::<Vec<Entity>>(), vec![ d ]);
+        assert_eq!(ecs.siblings(root).count(), 0);
+
+        // Traversals terminate even if a cycle was sneaked in past the Ecs
+        let e = ecs.add_entity();
+        let f = ecs.add_entity();
+        assert!(ecs.set_parent(e, f));
+        ecs.list_component_mut::<Relation<ChildOf>>().insert(f, Relation::new(e));
+        assert_eq!(ecs.ancestors(e).collect::<Vec<Entity>>(), vec![ f ]);
+        assert_eq!(ecs.descendants(e, TraversalOrder::DepthFirst).collect::<Vec<Entity>>(), vec![ f ]);
+        assert_eq!(ecs.descendants(e, TraversalOrder::BreadthFirst).collect::<Vec<Entity>>(), vec![ f ]);
+        assert!(ecs.set_parent(d, e));
+        ecs.remove_entity(e);
+        ecs.remove_entity(f);
+
+        // Removing a parent turns its children into roots
+        ecs.remove_entity(a);
+        assert_eq!(ecs.parent(c), None);
+        assert_eq!(ecs.children(root), vec![ b ]);
+        assert!(ecs.check_integrity().is_empty());
+    }
+
+    /// Tests running one-shot systems
+    #[test]
+    fn test_oneshot_systems() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        let ecs = ecs.borrow();
+
+        // Register a system that spawns entities with the given health
+        let spawn: SystemId<u32, Entity> = ecs.register_system(|ecs: &Ecs, health: u32| {
+            let entity = ecs.add_entity();
+            ecs.add_component(entity, Health(health));
+            entity
+        });
+        let entity = ecs.run_system_with_input(spawn, 42).unwrap();
+        assert_eq!(ecs.get_component::<Health>(entity).unwrap().0, 42);
+
+        // Register one that runs another
+        let count: SystemId<(), usize> = ecs.register_system(move |ecs: &Ecs, _: ()| {
+            ecs.run_system_with_input(spawn, 1).unwrap();
+            ecs.list_component::<Health>().len()
+        });
+        assert_eq!(ecs.run_system(count).unwrap(), 2);
+
+        // Systems may not run themselves, and removed systems cannot be run
+        let recursive: SystemId = ecs.register_system(|ecs: &Ecs, _: ()| {
+            assert!(matches!(ecs.run_system(SystemId::<(), ()>::new(2)), Err(Error::SystemAlreadyRunning{ .. })));
+        });
+        ecs.run_system(recursive).unwrap();
+        assert!(ecs.remove_system(recursive));
+        assert!(matches!(ecs.run_system(recursive), Err(Error::UnknownSystem{ .. })));
+
+        // Systems that panic can still be run afterwards
+        let fallible: SystemId<bool, ()> = ecs.register_system(|_: &Ecs, fail: bool| { if fail { panic!("System failed"); } });
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| ecs.run_system_with_input(fallible, true))).is_err());
+        assert!(ecs.run_system_with_input(fallible, false).is_ok());
+    }
+
+    /// Tests recording and applying command buffers
+    #[test]
+    fn test_commands() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register::<Player>(&ecs);
+        let ecs = ecs.borrow();
+        let existing = ecs.add_entity();
+
+        // Record two buffers that touch the same entity
+        let mut first = CommandBuffer::new();
+        let entity = first.spawn(&ecs.entity_reserver());
+        first.add_component(entity, Health(1));
+        first.add_component(existing, Player);
+        let mut second = CommandBuffer::new();
+        second.add_component(entity, Health(2));
+        second.despawn(existing);
+        assert_eq!(first.len(), 3);
+
+        // Nothing should have happened yet, not even when flushing otherwise
+        ecs.flush_entities();
+        ecs.add_entity();
+        assert!(!ecs.is_alive(entity));
+        assert!(ecs.list_component::<Player>().is_empty());
+
+        // Apply them in order; the second should win
+        ecs.apply_commands([ first, second ]);
+        assert_eq!(ecs.get_component::<Health>(entity).unwrap().0, 2);
+        assert!(!ecs.is_alive(existing));
+        assert!(ecs.list_component::<Player>().is_empty());
+
+        // Buffers may also be filled on worker threads
+        let reserver: EntityReserver = ecs.entity_reserver();
+        let buffers: Vec<CommandBuffer> = std::thread::scope(|s| {
+            let workers: Vec<_> = (0..4).map(|i| {
+                let reserver: EntityReserver = reserver.clone();
+                s.spawn(move || {
+                    let mut buffer = CommandBuffer::new();
+                    let entity = buffer.spawn(&reserver);
+                    buffer.add_component(entity, Health(i));
+                    buffer
+                })
+            }).collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+        ecs.apply_commands(buffers);
+        let mut healths: Vec<u32> = ecs.list_component::<Health>().iter().map(|h| h.0).collect();
+        healths.sort();
+        assert_eq!(healths, vec![ 0, 1, 2, 2, 3 ]);
+
+        // Entities spawned in a buffer that is never applied never become alive
+        let mut dropped = CommandBuffer::new();
+        let entity = dropped.spawn(&reserver);
+        drop(dropped);
+        ecs.apply_commands([]);
+        ecs.add_entity();
+        assert!(!ecs.is_alive(entity));
+    }
+
+    /// Tests the dynamic query builder
+    #[test]
+    fn test_query_builder() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register::<Player>(&ecs);
+        let ecs = ecs.borrow();
+
+        let a: Entity = ecs.add_entity();
+        let b: Entity = ecs.add_entity();
+        let c: Entity = ecs.add_entity();
+        ecs.add_component(a, Health(10));
+        ecs.add_component(b, Health(50));
+        ecs.add_component(b, Player);
+        ecs.add_component(c, Health(80));
+
+        // Find the components by name
+        let health: TypeId = ecs.component_type_id("Health").unwrap();
+        let player: TypeId = ecs.component_type_id(std::any::type_name::<Player>()).unwrap();
+        assert!(ecs.component_type_id("Mana").is_none());
+
+        // Run some queries
+        let mut result: Vec<Entity> = QueryBuilder::new().with_predicate(health, |h| h.downcast_ref::<Health>().unwrap().0 > 20).iter(&ecs).unwrap().collect();
+        result.sort();
+        assert_eq!(result, vec![ b, c ]);
+        let result: Vec<Entity> = QueryBuilder::new().with(health).without(player).with_predicate(health, |h| h.downcast_ref::<Health>().unwrap().0 > 20).iter(&ecs).unwrap().collect();
+        assert_eq!(result, vec![ c ]);
+        assert_eq!(QueryBuilder::new().iter(&ecs).unwrap().count(), 3);
+        assert!(matches!(QueryBuilder::new().with(TypeId::of::<u32>()).iter(&ecs), Err(Error::UnregisteredComponent{ .. })));
+
+        // Cardinality operations agree with iterating
+        assert_eq!(QueryBuilder::new().with(health).count(&ecs).unwrap(), 3);
+        assert_eq!(QueryBuilder::new().with(health).without(player).count(&ecs).unwrap(), 2);
+        assert!(!QueryBuilder::new().with(player).is_empty(&ecs).unwrap());
+        assert!(QueryBuilder::new().with(player).with_predicate(health, |h| h.downcast_ref::<Health>().unwrap().0 > 60).is_empty(&ecs).unwrap());
+        assert!(QueryBuilder::new().with(player).contains(&ecs, b).unwrap());
+        assert!(!QueryBuilder::new().with(player).contains(&ecs, a).unwrap());
+        ecs.remove_entity(c);
+        assert!(!QueryBuilder::new().without(player).contains(&ecs, c).unwrap());
+    }
+
+    /// Tests split borrows of multiple lists
+    #[test]
+    fn test_split() {
+        use crate::split::{Read, Write};
+
+        let (ecs, [ a, b ]) = crate::world!(Health, Player;
+            { Health(10), Player },
+            { Health(20) },
+        );
+        let ecs = ecs.borrow();
+
+        // Update one list based on the other
+        let updated: usize = ecs.split::<(Write<Health>, Read<Player>), _>(|(healths, players)| {
+            let mut updated: usize = 0;
+            healths.for_each_mut(|entity, health| {
+                if players.get(entity).is_some() { health.0 += 5; updated += 1; }
+            });
+            updated
+        }).unwrap();
+        assert_eq!(updated, 1);
+        assert_eq!(ecs.get_component::<Health>(a).unwrap().0, 15);
+        assert_eq!(ecs.get_component::<Health>(b).unwrap().0, 20);
+
+        // Invalid access sets are refused
+        assert!(matches!(ecs.split::<(Write<Health>, Read<Health>), _>(|_| ()), Err(Error::DuplicateAccess{ .. })));
+        assert!(matches!(ecs.split::<(Read<Health>, Read<Previous<Health>>), _>(|_| ()), Err(Error::UnregisteredComponent{ .. })));
+    }
+
+    /// Tests extracting entities into another Ecs
+    #[test]
+    fn test_extract_filtered() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Score(u32);
+        impl Component for Score {}
+        #[derive(Clone)]
+        struct Tag;
+        impl Component for Tag {}
+
+        let (source, [ a, b, c ]) = crate::world!(Score, Player;
+            { Score(1), Player },
+            { Score(2) },
+            { Player },
+        );
+        let source = source.borrow();
+        let target = crate::world!(Score);
+        let target = target.borrow();
+
+        // Preserving IDs copies only the selected types of matching entities
+        let mapping = source.extract_filtered::<(Score,)>(&target, ExtractIds::Preserve, |e| e != b).unwrap();
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(mapping[&a], a);
+        assert!(target.is_alive(a) && !target.is_alive(b) && target.is_alive(c));
+        assert_eq!(*target.get_component::<Score>(a).unwrap(), Score(1));
+        assert!(target.get_component::<Score>(c).is_none());
+        assert!(target.add_entity() > c);
+
+        // Extracting again keeps the target in sync
+        source.remove_component::<Score>(a);
+        source.extract_filtered::<(Score,)>(&target, ExtractIds::Preserve, |e| e == a).unwrap();
+        assert!(target.get_component::<Score>(a).is_none());
+
+        // Remapping spawns new entities
+        let mapping = source.extract_filtered::<(Score,)>(&target, ExtractIds::Remap, |e| e == b).unwrap();
+        assert_ne!(mapping[&b], b);
+        assert_eq!(*target.get_component::<Score>(mapping[&b]).unwrap(), Score(2));
+
+        // Unregistered types are refused
+        assert!(matches!(source.extract_filtered::<(Score, Tag)>(&target, ExtractIds::Remap, |_| true), Err(Error::UnregisteredComponent{ .. })));
+
+        // So is extracting into the source itself
+        assert!(matches!(source.extract_filtered::<(Score,)>(&source, ExtractIds::Remap, |_| true), Err(Error::ExtractIntoSelf)));
+
+        // Preserved IDs that have been removed in the target are not revived
+        assert!(target.remove_entity(c));
+        assert!(matches!(source.extract_filtered::<(Score,)>(&target, ExtractIds::Preserve, |e| e == c), Err(Error::UnavailableEntityId{ .. })));
+        assert!(!target.is_alive(c));
+        assert!(matches!(target.spawn_with_id(Entity::PLACEHOLDER), Err(Error::UnavailableEntityId{ .. })));
+
+        // Components refused by the target (here, a full fixed-capacity list) are reported
+        let fixed = Ecs::new(16);
+        Ecs::register_fixed::<Score>(&fixed, 1);
+        let fixed = fixed.borrow();
+        source.add_component(a, Score(3));
+        assert!(matches!(source.extract_filtered::<(Score,)>(&fixed, ExtractIds::Remap, |_| true), Err(Error::ComponentRejected{ .. })));
+
+        // And so is exceeding the entity quota of the target
+        let limited = crate::world!(Score);
+        limited.borrow_mut().set_entity_quota(Quota::new().with_hard(1));
+        let limited = limited.borrow();
+        assert!(matches!(source.extract_filtered::<(Score,)>(&limited, ExtractIds::Remap, |_| true), Err(Error::QuotaExceeded{ .. })));
+    }
+
+    /// Tests hashing the state of the Ecs
+    #[test]
+    fn test_state_hash() {
+        use crate::wire::{WireCodec, WireReader, WireWriter};
+
+        #[derive(Debug)]
+        struct Score(u32);
+        impl Component for Score {}
+        impl WireCodec for Score {
+            fn encode(&self, writer: &mut WireWriter) { self.0.encode(writer); }
+            fn decode(reader: &mut WireReader) -> Result<Self, Error> { Ok(Self(u32::decode(reader)?)) }
+        }
+        #[derive(Debug)]
+        struct Hp(u32);
+        impl Component for Hp {}
+        impl WireCodec for Hp {
+            fn encode(&self, writer: &mut WireWriter) { self.0.encode(writer); }
+            fn decode(reader: &mut WireReader) -> Result<Self, Error> { Ok(Self(u32::decode(reader)?)) }
+        }
+
+        // Two worlds built the same way (in a different order) hash the same
+        let (first, [ a, _ ]) = crate::world!(Score, Hp;
+            { Score(1), Hp(10) },
+            { Score(2) },
+        );
+        let first = first.borrow();
+        let second = crate::world!(Score, Hp);
+        let second = second.borrow();
+        let (x, y) = (second.add_entity(), second.add_entity());
+        second.add_component(y, Score(2));
+        second.add_component(x, Hp(10));
+        second.add_component(x, Score(1));
+        let hash: StateHash = first.state_hash::<(Score, Hp)>();
+        assert_eq!(hash, second.state_hash::<(Score, Hp)>());
+        assert!(hash.diverging(&second.state_hash::<(Score, Hp)>()).is_empty());
+
+        // Changes are localized to the component type
+        first.get_component_mut::<Hp>(a).unwrap().0 = 11;
+        let other: StateHash = first.state_hash::<(Score, Hp)>();
+        assert_ne!(hash.total, other.total);
+        assert_eq!(hash.diverging(&other), vec![ std::any::type_name::<Hp>() ]);
+        second.remove_entity(y);
+        assert_eq!(hash.diverging(&second.state_hash::<(Score, Hp)>()), vec![ "entities", std::any::type_name::<Score>() ]);
+    }
+
+    /// Tests entity and component quotas
+    #[test]
+    fn test_quotas() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register::<Player>(&ecs);
+        {
+            let mut ecs = ecs.borrow_mut();
+            ecs.set_entity_quota(Quota::new().with_soft(2).with_hard(3));
+            ecs.set_component_quota::<Health>(Quota::new().with_soft(1).with_hard(2));
+            ecs.insert_resource(Events::<QuotaWarning>::new());
+            ecs.insert_resource(Events::<QuotaRejection>::new());
+        }
+        let ecs = ecs.borrow();
+
+        // Entities are limited
+        let entities: Vec<Entity> = (0..3).map(|_| ecs.try_add_entity().unwrap()).collect();
+        assert!(matches!(ecs.try_add_entity(), Err(Error::QuotaExceeded{ what: "entities", limit: 3 })));
+
+        // Components are limited, but overwriting does not count
+        assert!(ecs.add_component(entities[0], Health(1)));
+        assert!(ecs.add_component(entities[1], Health(2)));
+        assert!(!ecs.add_component(entities[2], Health(3)));
+        assert!(ecs.add_component(entities[1], Health(4)));
+        ecs.remove_component::<Health>(entities[0]);
+        assert!(ecs.add_component(entities[2], Health(3)));
+
+        // Crossing the soft limits was reported every time (but not for overwrites)
+        let events = ecs.resource::<Events<QuotaWarning>>().unwrap();
+        assert_eq!(events.iter().cloned().collect::<Vec<QuotaWarning>>(), vec![
+            QuotaWarning{ what: "entities", count: 3, limit: 2 },
+            QuotaWarning{ what: std::any::type_name::<Health>(), count: 2, limit: 1 },
+            QuotaWarning{ what: std::any::type_name::<Health>(), count: 2, limit: 1 },
+        ]);
+        drop(events);
+
+        // Entities spawned through CommandBuffers are limited as well
+        ecs.remove_entity(entities[0]);
+        let reserver: EntityReserver = ecs.entity_reserver();
+        let mut buffer = CommandBuffer::new();
+        let spawned: Vec<Entity> = (0..4).map(|_| buffer.spawn(&reserver)).collect();
+        for entity in &spawned { buffer.add_component(*entity, Player); }
+        ecs.apply_commands([ buffer ]);
+        assert_eq!(ecs.entities().len(), 3);
+        assert_eq!(spawned.iter().filter(|e| ecs.is_alive(**e)).count(), 1);
+        assert_eq!(ecs.list_component::<Player>().len(), 1);
+
+        // So are reserved entities, and discarding them does not count as a structural change
+        let generation: u64 = ecs.generation();
+        let reserved: Vec<Entity> = (0..2).map(|_| ecs.reserve_entity()).collect();
+        ecs.flush_entities();
+        assert!(reserved.iter().all(|e| !ecs.is_alive(*e)));
+        assert_eq!(ecs.generation(), generation);
+
+        // Every discarded entity was reported
+        let events = ecs.resource::<Events<QuotaRejection>>().unwrap();
+        assert_eq!(events.iter().cloned().collect::<Vec<QuotaRejection>>(), vec![
+            QuotaRejection{ what: "entities", discarded: 1, limit: 3 },
+            QuotaRejection{ what: "entities", discarded: 1, limit: 3 },
+            QuotaRejection{ what: "entities", discarded: 1, limit: 3 },
+            QuotaRejection{ what: "entities", discarded: 2, limit: 3 },
+        ]);
+    }
+
+    /// Tests the world integrity checker
+    #[test]
+    fn test_check_integrity() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register_hierarchy(&ecs);
+        let ecs = ecs.borrow();
+        let a = ecs.add_entity();
+        let b = ecs.add_entity();
+        ecs.add_component(a, Health(10));
+        assert!(ecs.set_parent(b, a));
+        assert!(ecs.check_integrity().is_empty());
+
+        // Removing entities through the Ecs keeps the world intact
+        ecs.remove_entity(a);
+        assert!(ecs.check_integrity().is_empty());
+
+        // Bypassing the Ecs does not
+        ecs.list_component_mut::<Health>().insert(a, Health(5));
+        let c = ecs.add_entity();
+        assert!(ecs.relate::<ChildOf>(b, c));
+        ecs.list_component_mut::<Relation<ChildOf>>().insert(c, Relation::new(b));
+        let mut expected: Vec<String> = vec![
+            format!("entity {}: hierarchy contains a cycle", b),
+            format!("entity {}: hierarchy contains a cycle", c),
+            format!("entity {}: {} belongs to a dead entity", a, std::any::type_name::<Health>()),
+        ];
+        expected.sort();
+        assert_eq!(ecs.check_integrity(), expected);
+    }
+
+    /// Tests detecting and removing orphaned components
+    #[test]
+    fn test_orphans() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        ecs.borrow_mut().insert_resource(Events::<OrphanedComponent>::new());
+        let ecs = ecs.borrow();
+        let a = ecs.add_entity();
+        let b = ecs.add_entity();
+        ecs.add_component(b, Health(10));
+        ecs.remove_entity(a);
+        assert_eq!(ecs.clean_orphans(OrphanPolicy::Remove), 0);
+
+        // Leave an orphan behind by bypassing the Ecs
+        ecs.list_component_mut::<Health>().insert(a, Health(5));
+        assert_eq!(ecs.find_orphans(), vec![ (a, std::any::type_name::<Health>()) ]);
+        let generation: u64 = ecs.generation();
+        assert_eq!(ecs.clean_orphans(OrphanPolicy::Report), 1);
+        assert_eq!(ecs.generation(), generation);
+        assert_eq!(ecs.clean_orphans(OrphanPolicy::Remove), 1);
+        assert!(ecs.generation() > generation);
+        assert!(ecs.find_orphans().is_empty());
+        assert_eq!(ecs.get_component::<Health>(b).unwrap().0, 10);
+
+        // Both runs were reported
+        let events = ecs.resource::<Events<OrphanedComponent>>().unwrap();
+        assert_eq!(events.iter().map(|e| e.removed).collect::<Vec<bool>>(), vec![ false, true ]);
+    }
+
+    /// Tests the structural journal
+    #[test]
+    fn test_journal() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        ecs.borrow_mut().enable_journal(2);
+        let ecs = ecs.borrow();
+
+        // Record some (attributed) operations
+        let a = ecs.attribute("spawner", || {
+            let a = ecs.add_entity();
+            ecs.add_component(a, Health(10));
+            ecs.add_component(a, Health(5));
+            a
+        });
+        ecs.increment_tick();
+        ecs.attribute("cleaner", || { ecs.remove_component::<Health>(a); ecs.remove_entity(a) });
+        let changes: Vec<(Option<&'static str>, StructuralOp)> = ecs.journal().unwrap().of_entity(a).map(|c| (c.system, c.op)).collect();
+        assert_eq!(changes, vec![
+            (Some("spawner"), StructuralOp::Spawn),
+            (Some("spawner"), StructuralOp::Insert(std::any::type_name::<Health>())),
+            (Some("cleaner"), StructuralOp::Remove(std::any::type_name::<Health>())),
+            (Some("cleaner"), StructuralOp::Despawn),
+        ]);
+
+        // Old frames are dropped, and unattributed operations are recorded as such
+        ecs.increment_tick();
+        let b = ecs.add_entity();
+        let journal = ecs.journal().unwrap();
+        assert_eq!(journal.len(), 3);
+        assert_eq!(journal.iter().last().unwrap().to_string(), format!("[tick {}] <unknown>: spawn entity {}", ecs.change_tick(), b));
+    }
+
+    /// Tests that the structural journal also records indirect changes
+    #[test]
+    fn test_journal_indirect() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register_hierarchy(&ecs);
+        ecs.borrow_mut().enable_journal(16);
+        let ecs = ecs.borrow();
+        let (a, b, parent) = (ecs.add_entity(), ecs.add_entity(), ecs.add_entity());
+        let ops = |entity: Entity| -> Vec<StructuralOp> { ecs.journal().unwrap().of_entity(entity).map(|c| c.op).skip(1).collect() };
+
+        // Swapping a component to an entity without one moves it
+        ecs.add_component(a, Health(1));
+        assert!(ecs.swap_component::<Health>(a, b));
+        let health: &'static str = std::any::type_name::<Health>();
+        assert_eq!(ops(a), vec![ StructuralOp::Insert(health), StructuralOp::Remove(health) ]);
+        assert_eq!(ops(b), vec![ StructuralOp::Insert(health) ]);
+
+        // Removing a parent removes the link from its (living) children
+        assert!(ecs.set_parent(b, parent));
+        ecs.remove_entity(parent);
+        let child_of: &'static str = std::any::type_name::<Relation<ChildOf>>();
+        assert_eq!(ops(b), vec![ StructuralOp::Insert(health), StructuralOp::Insert(child_of), StructuralOp::Remove(child_of) ]);
+
+        // Removing orphans is recorded as well
+        ecs.remove_entity(a);
+        ecs.list_component_mut::<Health>().insert(a, Health(2));
+        assert_eq!(ecs.clean_orphans(OrphanPolicy::Remove), 1);
+        assert_eq!(ops(a).last(), Some(&StructuralOp::Remove(health)));
+    }
+
+    /// Tests component validation hooks
+    #[test]
+    fn test_validation() {
+        #[derive(Debug)]
+        struct Speed(f32);
+        impl Component for Speed {}
+        impl Validate for Speed {
+            fn validate(&self) -> Result<(), String> {
+                if self.0.is_nan() { Err("speed is NaN".into()) } else { Ok(()) }
+            }
+        }
+
+        let ecs = crate::world!(Speed);
+        Ecs::register_validator::<Speed>(&ecs);
+        let ecs = ecs.borrow();
+        let entity: Entity = ecs.add_entity();
+        assert!(ecs.add_component(entity, Speed(1.0)));
+        assert!(ecs.validate_components().is_empty());
+
+        // Mutations are caught by validating everything
+        ecs.get_component_mut::<Speed>(entity).unwrap().0 = f32::NAN;
+        assert_eq!(ecs.validate_components(), vec![ format!("entity {}: invalid {}: speed is NaN", entity, std::any::type_name::<Speed>()) ]);
+
+        // Adding an invalid component panics (in debug builds)
+        #[cfg(debug_assertions)]
+        {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| ecs.add_component(entity, Speed(f32::NAN))));
+            assert!(result.is_err());
+        }
+    }
+
+    /// Tests the read-only world view
+    #[test]
+    fn test_view() {
+        let (ecs, [ a, b ]) = crate::world!(Health, Player;
+            { Health(10), Player },
+            { Health(20) },
+        );
+        let ecs = ecs.borrow();
+
+        // The view sees everything the Ecs sees
+        let view: WorldView = ecs.view();
+        assert_eq!(view.entities().len(), 2);
+        assert!(view.is_alive(a));
+        assert_eq!(view.get_component::<Health>(b).unwrap().0, 20);
+        assert!(view.get_component::<Player>(b).is_none());
+        let mut total: u32 = 0;
+        view.for_each::<Health>(|_, health| { total += health.0; });
+        assert_eq!(total, 30);
+
+        // Guards from the view outlive the view itself
+        let list = { ecs.view().list_component::<Health>() };
+        assert_eq!(list.len(), 2);
+    }
+
+    /// Tests the structural generation counter
+    #[test]
+    fn test_generation() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register::<Player>(&ecs);
+        let ecs = ecs.borrow();
+
+        // Structural changes bump the counter
+        let mut last: u64 = ecs.generation();
+        let entity: Entity = ecs.add_entity();
+        assert!(ecs.generation() > last);
+        last = ecs.generation();
+        ecs.add_component(entity, Health(10));
+        assert!(ecs.generation() > last);
+        last = ecs.generation();
+
+        // Changing a value does not
+        ecs.get_component_mut::<Health>(entity).unwrap().0 = 5;
+        assert_eq!(ecs.generation(), last);
+        assert!(ecs.add_component(entity, Health(7)));
+        assert_eq!(ecs.generation(), last);
+        assert!(ecs.remove_component::<Player>(entity).is_none());
+        assert_eq!(ecs.generation(), last);
+        assert!(ecs.remove_components::<(Player,)>(entity).0.is_none());
+        assert_eq!(ecs.generation(), last);
+
+        // Removing does again
+        ecs.remove_entity(entity);
+        assert!(ecs.generation() > last);
+    }
+
+    /// Tests double-buffered components
+    #[test]
+    fn test_previous() {
+        #[derive(Clone)]
+        struct Position(f32);
+        impl Component for Position {}
+
+        let ecs = Ecs::new(16);
+        Ecs::register::<Position>(&ecs);
+        Ecs::register_previous::<Position>(&ecs);
+        let ecs = ecs.borrow();
+
+        let a: Entity = ecs.add_entity();
+        let b: Entity = ecs.add_entity();
+        ecs.add_component(a, Position(1.0));
+        ecs.add_component(b, Position(2.0));
+        assert!(ecs.get_previous::<Position>(a).is_none());
+
+        // Store the values and change them
+        ecs.store_previous();
+        ecs.get_component_mut::<Position>(a).unwrap().0 = 3.0;
+        ecs.remove_component::<Position>(b);
+        assert_eq!(ecs.get_component::<Position>(a).unwrap().0, 3.0);
+        assert_eq!(ecs.get_previous::<Position>(a).unwrap().0, 1.0);
+        assert_eq!(ecs.get_previous::<Position>(b).unwrap().0, 2.0);
+
+        // The next store updates and drops buffers, which is a structural change
+        let generation: u64 = ecs.generation();
+        ecs.store_previous();
+        assert_eq!(ecs.get_previous::<Position>(a).unwrap().0, 3.0);
+        assert!(ecs.get_previous::<Position>(b).is_none());
+        assert_eq!(ecs.generation(), generation + 1);
+
+        // Merely updating the buffers is not
+        ecs.store_previous();
+        assert_eq!(ecs.generation(), generation + 1);
+    }
+
+    /// Tests the declarative world and spawn macros
+    #[test]
+    fn test_world_spawn_macros() {
+        let (ecs, [ a, b ]) = crate::world!(Health, Player;
+            { Health(10), Player },
+            { Health(3) },
+        );
+        let ecs = ecs.borrow();
+        assert_eq!(ecs.get_component::<Health>(a).unwrap().0, 10);
+        assert!(ecs.get_component::<Player>(a).is_some());
+        assert_eq!(ecs.get_component::<Health>(b).unwrap().0, 3);
+        assert!(ecs.get_component::<Player>(b).is_none());
+
+        let c: Entity = crate::spawn!(ecs, { Player });
+        assert!(ecs.get_component::<Player>(c).is_some());
+        let d: Entity = crate::spawn!(ecs, {});
+        assert!(ecs.is_alive(d));
+    }
+
+    /// Tests closure-based iteration
+    #[test]
+    fn test_for_each() {
+        let (ecs, [ a, b ]) = crate::world!(Health;
+            { Health(10) },
+            { Health(20) },
+        );
+        let ecs = ecs.borrow();
+
+        ecs.for_each_mut::<Health>(|_, health| { health.0 *= 2; });
+        let mut seen: Vec<(Entity, u32)> = vec![];
+        ecs.for_each::<Health>(|e, health| { seen.push((e, health.0)); });
+        seen.sort();
+        assert_eq!(seen, vec![ (a, 20), (b, 40) ]);
+
+        // Still correct after removing and swapping
+        ecs.swap_component::<Health>(a, b);
+        ecs.remove_component::<Health>(a);
+        let mut seen: Vec<(Entity, u32)> = vec![];
+        ecs.for_each::<Health>(|e, health| { seen.push((e, health.0)); });
+        assert_eq!(seen, vec![ (b, 20) ]);
+    }
+
+    /// Tests fixed-capacity component lists
+    #[test]
+    fn test_register_fixed() {
+        let ecs = Ecs::new(16);
+        Ecs::register_fixed::<Player>(&ecs, 2);
+        let ecs = ecs.borrow();
+        assert_eq!(ecs.list_component::<Player>().fixed_capacity(), Some(2));
+
+        let a: Entity = ecs.add_entity();
+        let b: Entity = ecs.add_entity();
+        let c: Entity = ecs.add_entity();
+        assert!(ecs.add_component(a, Player));
+        assert!(ecs.add_component(b, Player));
+        assert!(ecs.add_component(b, Player));
+        assert!(!ecs.add_component(c, Player));
+
+        // Space is freed up by removing
+        ecs.remove_component::<Player>(a);
+        assert!(ecs.add_component(c, Player));
+    }
+
+    /// Tests pooled components
+    #[test]
+    fn test_pooled() {
+        #[derive(Default)]
+        struct Path(Vec<u32>);
+        impl Component for Path {}
+        impl Poolable for Path {
+            fn reset(&mut self) { self.0.clear(); }
+        }
+
+        let ecs = Ecs::new(16);
+        Ecs::register_pooled::<Path>(&ecs, 1);
+        let ecs = ecs.borrow();
+
+        // Despawning an entity pools its component
+        let a: Entity = ecs.add_entity();
+        assert!(ecs.add_pooled(a, |p: &mut Path| p.0.extend(0..100)));
+        ecs.remove_entity(a);
+        assert_eq!(ecs.list_component::<Path>().pooled(), 1);
+
+        // Adding reuses it, after resetting
+        let b: Entity = ecs.add_entity();
+        assert!(ecs.add_pooled(b, |p: &mut Path| p.0.push(42)));
+        assert_eq!(ecs.list_component::<Path>().pooled(), 0);
+        assert_eq!(ecs.get_component::<Path>(b).unwrap().0, vec![ 42 ]);
+        assert!(ecs.get_component::<Path>(b).unwrap().0.capacity() >= 100);
+
+        // The pool is bounded
+        let c: Entity = ecs.add_entity();
+        ecs.add_pooled(c, |_: &mut Path| {});
+        ecs.clear_component::<Path>();
+        assert_eq!(ecs.list_component::<Path>().pooled(), 1);
+    }
+
+    /// Tests state machines and their transition events
+    #[test]
+    fn test_state_machines() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Door { Closed, Open }
+
+        let ecs = Ecs::new(16);
+        Ecs::register::<Player>(&ecs);
+        Ecs::register_state_machine::<Door>(&ecs);
+        let ecs = ecs.borrow();
+
+        // Doors open when a player is near, and close again otherwise
+        let player: Entity = ecs.add_entity();
+        ecs.add_transition(Door::Closed, Door::Open, move |ecs, _| ecs.get_component::<Player>(player).is_some());
+        ecs.add_transition(Door::Open, Door::Closed, move |ecs, _| ecs.get_component::<Player>(player).is_none());
+        let door: Entity = crate::spawn!(ecs, { StateMachine::new(Door::Closed) });
+        let mut reader = ecs.resource::<Events<StateChanged<Door>>>().unwrap().get_reader();
+
+        assert_eq!(ecs.update_state_machines::<Door>(), 0);
+        ecs.add_component(player, Player);
+        assert_eq!(ecs.update_state_machines::<Door>(), 1);
+        assert_eq!(ecs.get_component::<StateMachine<Door>>(door).unwrap().state(), &Door::Open);
+        assert_eq!(ecs.update_state_machines::<Door>(), 0);
+
+        // The change is reported
+        let events = ecs.resource::<Events<StateChanged<Door>>>().unwrap();
+        assert_eq!(reader.read(&events).cloned().collect::<Vec<_>>(), vec![ StateChanged{ entity: door, from: Door::Closed, to: Door::Open } ]);
+        drop(events);
+
+        // Machines removed while evaluating the conditions are not counted
+        let door: Entity = crate::spawn!(ecs, { StateMachine::new(Door::Closed) });
+        ecs.add_transition(Door::Closed, Door::Open, move |ecs, entity| { ecs.remove_component::<StateMachine<Door>>(entity); true });
+        ecs.remove_component::<Player>(player);
+        assert_eq!(ecs.update_state_machines::<Door>(), 1);
+        assert!(ecs.get_component::<StateMachine<Door>>(door).is_none());
+        let events = ecs.resource::<Events<StateChanged<Door>>>().unwrap();
+        assert_eq!(reader.read(&events).map(|c| c.to.clone()).collect::<Vec<Door>>(), vec![ Door::Closed ]);
+    }
+
+    /// Tests capturing query snapshots
+    #[test]
+    fn test_snapshot() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Score(u32);
+        impl Component for Score {}
+        #[derive(Clone, Debug, PartialEq)]
+        struct Team(u8);
+        impl Component for Team {}
+
+        let (ecs, [ a, b, c ]) = crate::world!(Score, Team;
+            { Score(1), Team(0) },
+            { Score(2) },
+            { Score(3), Team(1) },
+        );
+        let ecs = ecs.borrow();
+
+        // Only entities with all components are captured, in order
+        let snapshot: QuerySnapshot<(Score, Team)> = ecs.snapshot();
+        assert_eq!(snapshot.entities().collect::<Vec<Entity>>(), vec![ a, c ]);
+        assert!(snapshot.get(b).is_none());
+        assert_eq!(snapshot.get(c), Some(&(Score(3), Team(1))));
+
+        // Later changes do not affect it
+        let scores: QuerySnapshot<(Score,)> = ecs.snapshot();
+        ecs.get_component_mut::<Score>(b).unwrap().0 = 20;
+        assert_eq!(scores.get(b), Some(&(Score(2),)));
+        assert_eq!(scores.len(), 3);
+
+        // Duplicate component types capture (and rewind) nothing instead of deadlocking
+        let duplicates: QuerySnapshot<(Score, Score)> = ecs.snapshot();
+        assert!(duplicates.is_empty());
+        assert_eq!(<(Score, Score)>::swap(&ecs, &mut [ (a, (Score(7), Score(8))) ], None), vec![ 0 ]);
+        assert_eq!(ecs.get_component::<Score>(a).unwrap().0, 1);
+
+        // Rewinding swaps the old values in, and back out again
+        let seen: Vec<u32> = ecs.rewind(&scores, |ecs| {
+            ecs.get_component_mut::<Score>(a).unwrap().0 = 100;
+            ecs.list_component::<Score>().iter().map(|score| score.0).collect()
+        });
+        assert_eq!(seen, vec![ 100, 2, 3 ]);
+        assert_eq!(ecs.get_component::<Score>(a).unwrap().0, 1);
+        assert_eq!(ecs.get_component::<Score>(b).unwrap().0, 20);
+
+        // The values are restored even if the closure panics
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| ecs.rewind(&scores, |_| panic!("Hit detection failed"))));
+        assert!(result.is_err());
+        assert_eq!(ecs.get_component::<Score>(b).unwrap().0, 20);
+    }
+
+    /// Tests exporting the component schema
+    #[test]
+    fn test_schema() {
+        let ecs = crate::world!(Health, Player);
+        let schema: ComponentSchema = ecs.borrow().schema();
+        assert_eq!(schema.version, SCHEMA_VERSION);
+        assert_eq!(schema.components.len(), 2);
+        assert_eq!(schema.get(std::any::type_name::<Health>()), Some(&ComponentInfo{ name: std::any::type_name::<Health>().into(), size: 4, align: 4, description: None }));
+        assert_eq!(schema.get(std::any::type_name::<Player>()).map(|c| c.size), Some(0));
+        assert_eq!(schema.to_json(), format!(
+            "{{\"version\":{},\"components\":[{{\"name\":\"{}\",\"size\":4,\"align\":4}},{{\"name\":\"{}\",\"size\":0,\"align\":1}}]}}",
+            SCHEMA_VERSION, std::any::type_name::<Health>(), std::any::type_name::<Player>(),
+        ));
+
+        // Described components also export a stable name and their fields
+        #[allow(dead_code)]
+        struct Armor { value: u32 }
+        impl Component for Armor {}
+        impl Describe for Armor {
+            const NAME: &'static str = "Armor";
+            fn fields() -> Vec<FieldInfo> { vec![ FieldInfo::new("value", "u32") ] }
+        }
+        Ecs::register::<Armor>(&ecs);
+        Ecs::register_description::<Armor>(&ecs);
+        let schema: ComponentSchema = ecs.borrow().schema();
+        assert_eq!(schema.get("Armor").and_then(|c| c.description.as_ref()), Some(&ComponentDescription{ name: "Armor".into(), fields: vec![ FieldInfo::new("value", "u32") ] }));
+        assert!(schema.to_json().contains(",\"stable_name\":\"Armor\",\"fields\":[{\"name\":\"value\",\"type\":\"u32\"}]}"));
+    }
+
+    /// Tests the query DSL macro
+    #[test]
+    fn test_query_macro() {
+        let (ecs, [ a, b, c ]) = crate::world!(Health, Player;
+            { Health(10), Player },
+            { Health(20) },
+            { Player },
+        );
+        let ecs = ecs.borrow();
+
+        // Heal only players
+        let mut healed: Vec<Entity> = vec![];
+        crate::query!(ecs, |e: Entity, health: &mut Health, _: With<Player>| {
+            health.0 += 5;
+            healed.push(e);
+        });
+        assert_eq!(healed, vec![ a ]);
+        assert_eq!(ecs.get_component::<Health>(a).unwrap().0, 15);
+        assert_eq!(ecs.get_component::<Health>(b).unwrap().0, 20);
+
+        // Filter on absence, and read-only access
+        let mut total: u32 = 0;
+        crate::query!(ecs, |health: &Health, _: Without<Player>,| { total += health.0; });
+        assert_eq!(total, 20);
+        let mut players: Vec<Entity> = vec![];
+        crate::query!(ecs, |_: With<Player>, e: Entity| { players.push(e); });
+        players.sort();
+        assert_eq!(players, vec![ a, c ]);
+        let mut both: Vec<Entity> = vec![];
+        crate::query!(ecs, |e: Entity, _: With<Player>, _: With<Health>| { both.push(e); });
+        assert_eq!(both, vec![ a ]);
+    }
+
+    /// Tests resources and their change detection
+    #[test]
+    fn test_resources() {
+        struct Settings { volume: u32 }
+
+        let ecs = Ecs::new(16);
+        let mut ecs = ecs.borrow_mut();
+        assert!(ecs.resource::<Settings>().is_none());
+        assert!(ecs.insert_resource(Settings{ volume: 5 }).is_none());
+
+        // It should be added and changed in this tick, but not the next
+        assert!(ecs.resource::<Settings>().unwrap().is_added());
+        assert!(ecs.resource::<Settings>().unwrap().is_changed());
+        let seen: u64 = ecs.change_tick();
+        ecs.increment_tick();
+        assert!(!ecs.resource::<Settings>().unwrap().is_changed());
+
+        // Reading through a muteable reference does not count as a change
+        assert_eq!(ecs.resource_mut::<Settings>().unwrap().volume, 5);
+        assert!(!ecs.resource::<Settings>().unwrap().is_changed());
+        ecs.resource_mut::<Settings>().unwrap().volume = 10;
+        assert!(ecs.resource::<Settings>().unwrap().is_changed());
+        assert!(ecs.resource::<Settings>().unwrap().is_changed_since(seen));
+
+        // Changes made after looking are noticed, even within the same tick
+        ecs.increment_tick();
+        let seen: u64 = ecs.change_tick();
+        assert!(!ecs.resource::<Settings>().unwrap().is_changed_since(seen));
+        ecs.resource_mut::<Settings>().unwrap().volume = 10;
+        assert!(ecs.resource::<Settings>().unwrap().is_changed_since(seen));
+
+        // Borrow the resource and the ECS at the same time
+        ecs.increment_tick();
+        let entity: Entity = ecs.resource_scope(|ecs: &mut Ecs, settings: &mut Settings| {
+            assert!(!ecs.has_resource::<Settings>());
+            settings.volume += 1;
+            ecs.add_entity()
+        }).unwrap();
+        assert!(ecs.is_alive(entity));
+        assert!(ecs.resource::<Settings>().unwrap().is_changed());
+        assert!(ecs.resource_scope(|_, _: &mut u32| ()).is_none());
+
+        // Remove it again
+        assert_eq!(ecs.remove_resource::<Settings>().map(|s| s.volume), Some(11));
+        assert!(!ecs.has_resource::<Settings>());
+    }
+
+    /// Tests (un)registering component types
+    #[test]
+    fn test_unregister() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        {
+            let ecs = ecs.borrow();
+            let entity = ecs.add_entity();
+            ecs.add_component(entity, Health(42));
+        }
+
+        // Unregister it, which should only work once
+        assert!(Ecs::unregister::<Health>(&ecs));
+        assert!(!Ecs::unregister::<Health>(&ecs));
+
+        // We should be able to re-register it, with an empty list
+        Ecs::register::<Health>(&ecs);
+        assert!(ecs.borrow().list_component::<Health>().is_empty());
+
+        // Any per-type settings are dropped as well
+        struct Mana(u32);
+        impl Component for Mana {}
+        impl Validate for Mana {
+            fn validate(&self) -> Result<(), String> { if self.0 > 100 { Err("too much mana".into()) } else { Ok(()) } }
+        }
+        impl Describe for Mana {
+            const NAME: &'static str = "Mana";
+            fn fields() -> Vec<FieldInfo> { vec![ FieldInfo::new("0", "u32") ] }
+        }
+        Ecs::register::<Mana>(&ecs);
+        Ecs::register_validator::<Mana>(&ecs);
+        Ecs::register_description::<Mana>(&ecs);
+        ecs.borrow_mut().set_component_quota::<Mana>(Quota::new().with_hard(1));
+        assert!(Ecs::unregister::<Mana>(&ecs));
+        {
+            let ecs = ecs.borrow();
+            let id: TypeId = ComponentList::<Mana>::id();
+            assert!(!ecs.validators.contains_key(&id) && !ecs.quotas.contains_key(&id) && !ecs.descriptions.contains_key(&id));
+        }
+        Ecs::register::<Mana>(&ecs);
+        let ecs = ecs.borrow();
+        let (a, b) = (ecs.add_entity(), ecs.add_entity());
+        assert!(ecs.add_component(a, Mana(200)));
+        assert!(ecs.add_component(b, Mana(1)));
+        assert!(ecs.validate_components().is_empty());
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A type-erased validation hook, which validates a single component.
+type Validator = fn(&dyn Any) -> Result<(), String>;
+
+/// Validates a single, type-erased component.
+/// 
+/// **Generic Types**
+///  * `T`: The Component type to validate.
+/// 
+/// **Arguments**
+///  * `component`: The component to validate. Must be of type `T`.
+fn validate_any<T: 'static + Validate>(component: &dyn Any) -> Result<(), String> {
+    component.downcast_ref::<T>().expect("Could not downcast component to its own type").validate()
+}
+
+
+
+/// Swaps the current values of rewound components back in when dropped, so `Ecs::rewind()` restores them even if its closure panics.
+/// 
+/// **Generic Types**
+///  * `Q`: The tuple of Component types that have been rewound.
+struct RewindGuard<'a, Q: Snapshot> {
+    /// The Ecs in which the components have been rewound.
+    ecs     : &'a Ecs,
+    /// The values to swap back in.
+    entries : Vec<(Entity, Q)>,
+    /// Marks which entries have actually been swapped out (see `Snapshot::swap()`).
+    mask    : Vec<u8>,
+}
+
+impl<'a, Q: Snapshot> Drop for RewindGuard<'a, Q> {
+    #[inline]
+    fn drop(&mut self) {
+        Q::swap(self.ecs, &mut self.entries, Some(&self.mask));
+    }
+}
+
+/// Puts a running one-shot system back in its slot when dropped, so `Ecs::run_system_with_input()` does not lose it if it panics.
+struct SystemGuard<'a> {
+    /// The Ecs that owns the system.
+    ecs    : &'a Ecs,
+    /// The ID of the system.
+    id     : u64,
+    /// The system itself. Only None while it is being put back.
+    system : Option<BoxedSystem>,
+}
+
+impl<'a> Drop for SystemGuard<'a> {
+    fn drop(&mut self) {
+        // Put it back (unless it was removed in the meantime)
+        if let Some(slot) = self.ecs.systems.lock().1.get_mut(&self.id) {
+            *slot = self.system.take();
+        }
+    }
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -30,240 +1331,1899 @@ use crate::list::ComponentList;
 pub struct Ecs {
     /// Data related to the entities in the ECS.
     /// 
-    /// # Layout
-    /// - `.0`: The last entity ID used.
-    /// - `.1`: The list of currently active entities.
-    entities   : RwLock<(u64, HashSet<Entity>)>,
-    /// The list of Window components
-    components : HashMap<TypeId, (&'static str, RwLock<Box<dyn ComponentListBase>>)>,
-}
+    /// # Layout
+    /// - `.0`: The first entity ID that has not yet been flushed to the list of active entities.
+    /// - `.1`: The list of currently active entities.
+    entities     : RwLock<(u64, EntityHashSet)>,
+    /// The next entity ID to hand out (starting at 1). Is atomic and shared so that entities may be reserved from other threads (see `Ecs::entity_reserver()`) without locking `entities`.
+    next_entity  : Arc<AtomicU64>,
+    /// The entity IDs reserved by CommandBuffers, which are skipped when flushing and only become alive once their buffer is applied (see `Ecs::spawn_deferred()`).
+    deferred     : Arc<Mutex<EntityHashSet>>,
+    /// The list of Window components
+    components   : HashMap<TypeId, (&'static str, RwLock<Box<dyn ComponentListBase>>)>,
+    /// The cleanup hooks for every registered relation kind, keyed by the ID of their Relation component.
+    relations    : HashMap<TypeId, RelationHooks>,
+    /// The hooks that store the previous value of every double-buffered component type, keyed by the ID of their Previous component.
+    previous     : HashMap<TypeId, fn(&Ecs)>,
+    /// The registered one-shot systems.
+    /// 
+    /// # Layout
+    /// - `.0`: The next system ID to hand out.
+    /// - `.1`: The systems themselves. A system is None while it is running.
+    systems      : Mutex<(u64, HashMap<u64, Option<BoxedSystem>>)>,
+    /// The resources (singleton values) in the ECS.
+    resources    : HashMap<TypeId, RwLock<ResourceData>>,
+    /// The current change tick, which is used for change detection.
+    change_tick  : AtomicU64,
+    /// Counts the structural changes (spawns, despawns, inserts and removes) made to the ECS.
+    generation   : AtomicU64,
+    /// Limits the number of entities.
+    entity_quota : Quota,
+    /// Limits the number of components per component type, keyed by their ID.
+    quotas       : HashMap<TypeId, Quota>,
+    /// The validation hooks of every validated component type, keyed by their ID.
+    validators   : HashMap<TypeId, Validator>,
+    /// Records the structural operations of the last few frames, if enabled.
+    journal      : Option<Mutex<Journal>>,
+    /// The descriptions of every described component type (see `Describe`), keyed by their ID.
+    descriptions : HashMap<TypeId, ComponentDescription>,
+}
+
+impl Ecs {
+    /// Constructor for the ECS.
+    /// 
+    /// **Arguments**
+    ///  * `initial_capacity`: The initial size of the internal vector (might be used to optimize)
+    /// 
+    /// # Returns
+    /// A new instance of the Ecs, already wrapped in an Rc + RefCell.
+    pub fn new(initial_capacity: usize) -> Rc<RefCell<Self>> {
+        debug!("Initialized Entity Component System v{}", env!("CARGO_PKG_VERSION"));
+        Rc::new(RefCell::new(Ecs {
+            entities     : RwLock::new((1, EntityHashSet::with_capacity_and_hasher(initial_capacity, Default::default()))),
+            next_entity  : Arc::new(AtomicU64::new(1)),
+            deferred     : Arc::new(Mutex::new(EntityHashSet::default())),
+            components   : HashMap::with_capacity(16),
+            relations    : HashMap::new(),
+            previous     : HashMap::new(),
+            systems      : Mutex::new((0, HashMap::new())),
+            resources    : HashMap::new(),
+            change_tick  : AtomicU64::new(1),
+            generation   : AtomicU64::new(0),
+            entity_quota : Quota::new(),
+            quotas       : HashMap::new(),
+            validators   : HashMap::new(),
+            journal      : None,
+            descriptions : HashMap::new(),
+        }))
+    }
+
+
+
+    /// Registers a new component type in the ECS.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The new Component type to register.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    #[inline]
+    pub fn register<T: 'static + Component>(this: &Rc<RefCell<Self>>) {
+        Self::register_list(this, ComponentList::<T>::default());
+    }
+
+    /// Registers a new component type in the ECS, which may only have a fixed number of components.
+    /// 
+    /// This is useful for components of which there are only a few (e.g., players or cameras). Their list allocates once and never reallocates, and `Ecs::add_component()` fails once it is full.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The new Component type to register.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    /// - `capacity`: The maximum number of components of this type.
+    #[inline]
+    pub fn register_fixed<T: 'static + Component>(this: &Rc<RefCell<Self>>, capacity: usize) {
+        Self::register_list(this, ComponentList::<T>::with_fixed_capacity(capacity));
+    }
+
+    /// Registers a new component type in the ECS, whose components are pooled for reuse.
+    /// 
+    /// Components of this type that are dropped by the ECS (because their entity is removed, they are cleared or they are overwritten) are kept in a pool instead, so that `Ecs::add_pooled()` can reuse their allocations. Components that are removed explicitly (e.g., `Ecs::remove_component()`) are returned to the caller as usual.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The new Component type to register.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    /// - `pool_size`: The maximum number of components to keep in the pool.
+    #[inline]
+    pub fn register_pooled<T: 'static + Poolable>(this: &Rc<RefCell<Self>>, pool_size: usize) {
+        Self::register_list(this, ComponentList::<T>::default().with_pool(pool_size));
+    }
+
+    /// Registers a new component type in the ECS, using the given (empty) list to store its components.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The new Component type to register.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    /// - `list`: The list to store the components in.
+    fn register_list<T: 'static + Component>(this: &Rc<RefCell<Self>>, list: ComponentList<T>) {
+        // Get the muteable reference
+        let mut mthis: RefMut<Self> = this.borrow_mut();
+
+        // Insert the new component type if it does not exist yet
+        if mthis.components.contains_key(&ComponentList::<T>::id()) { panic!("A component with ID {:?} already exists", ComponentList::<T>::id()); }
+        mthis.components.insert(ComponentList::<T>::id(), (
+            std::any::type_name::<T>(),
+            RwLock::new(Box::new(list)),
+        ));
+        *mthis.generation.get_mut() += 1;
+
+        // Also log the new component registration, but only if compiled with log support
+        debug!("Registered new Component type '{:?}'", ComponentList::<T>::id());
+    }
+
+    /// Unregisters a component type from the ECS, dropping all of its components.
+    /// 
+    /// Because this needs a muteable borrow of the ECS, it can only be done when nothing else is using it (i.e., at a safe point).
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to unregister.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self from which we unregister, wrapped in an Rc.
+    /// 
+    /// # Returns
+    /// True if the component type was unregistered, or false if it was not registered in the first place.
+    pub fn unregister<T: 'static + Component>(this: &Rc<RefCell<Self>>) -> bool {
+        // Get the muteable reference
+        let mut mthis: RefMut<Self> = this.borrow_mut();
+
+        // Remove the component type (and thus its list), and any hooks or settings for it
+        if mthis.components.remove(&ComponentList::<T>::id()).is_none() { return false; }
+        mthis.relations.remove(&ComponentList::<T>::id());
+        mthis.previous.remove(&ComponentList::<T>::id());
+        mthis.validators.remove(&ComponentList::<T>::id());
+        mthis.quotas.remove(&ComponentList::<T>::id());
+        mthis.descriptions.remove(&ComponentList::<T>::id());
+        *mthis.generation.get_mut() += 1;
+
+        // Also log the removal, but only if compiled with log support
+        debug!("Unregistered Component type '{:?}'", ComponentList::<T>::id());
+        true
+    }
+
+
+
+    /// Registers a new kind of relation in the ECS.
+    /// 
+    /// This registers `Relation<R>` as a component type, and makes sure relations are removed once their target entity is removed.
+    /// 
+    /// **Generic Types**
+    ///  * `R`: The new kind of relation to register.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    pub fn register_relation<R: 'static>(this: &Rc<RefCell<Self>>) {
+        // Register the component itself
+        Self::register::<Relation<R>>(this);
+
+        // Register the cleanup hook
+        this.borrow_mut().relations.insert(ComponentList::<Relation<R>>::id(), RelationHooks { cleanup: cleanup_relations::<R>, dangling: dangling_relations::<R> });
+    }
+
+    /// Registers the parent/child hierarchy in the ECS.
+    /// 
+    /// This is equivalent to registering the `ChildOf` relation.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    #[inline]
+    pub fn register_hierarchy(this: &Rc<RefCell<Self>>) {
+        Self::register_relation::<ChildOf>(this);
+    }
+
+    /// Enables double-buffering for the given component type.
+    /// 
+    /// This registers `Previous<T>` as a component type, which is updated to the current value of every `T` whenever `Ecs::store_previous()` is called. The component type itself must already be registered.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to double-buffer.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    pub fn register_previous<T: 'static + Component + Clone>(this: &Rc<RefCell<Self>>) {
+        // Register the buffer component itself
+        Self::register::<Previous<T>>(this);
+
+        // Register the hook that fills it
+        this.borrow_mut().previous.insert(ComponentList::<Previous<T>>::id(), store_previous::<T>);
+    }
+
+    /// Enables validation for the given component type.
+    /// 
+    /// In debug builds, every component of this type is validated when it is added (and the add panics if it is invalid), so invalid values are caught at the source. Changes made through mutable references cannot be intercepted; in any build, `Ecs::validate_components()` validates all existing ones (e.g., at the end of a frame or after loading a save). Components decoded from the wire format can be validated before they are added with `wire::decode_components_validated()`. The component type itself must already be registered.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to validate.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    pub fn register_validator<T: 'static + Validate>(this: &Rc<RefCell<Self>>) {
+        this.borrow_mut().validators.insert(ComponentList::<T>::id(), validate_any::<T>);
+    }
+
+    /// Adds the stable name and fields of the given component type (see `Describe`) to the schema (see `Ecs::schema()`).
+    /// 
+    /// The component type itself must already be registered.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to describe.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    pub fn register_description<T: 'static + Describe>(this: &Rc<RefCell<Self>>) {
+        this.borrow_mut().descriptions.insert(ComponentList::<T>::id(), ComponentDescription::of::<T>());
+    }
+
+    /// Finds the TypeId of a registered component type by its name.
+    /// 
+    /// This is useful for tools where the user types component names, like an in-game console.
+    /// 
+    /// **Arguments**
+    ///  * `name`: The name of the Component type. May either be the full path (e.g., `game::Health`) or just the name of the type (e.g., `Health`).
+    /// 
+    /// **Returns**  
+    /// The TypeId of the Component type, or None if no such type has been registered.
+    pub fn component_type_id(&self, name: &str) -> Option<TypeId> {
+        self.components.iter()
+            .find(|(_, (type_name, _))| *type_name == name || type_name.rsplit("::").next() == Some(name))
+            .map(|(id, _)| *id)
+    }
+
+    /// Describes all registered component types, so they may be exported for external tools.
+    /// 
+    /// **Returns**  
+    /// A ComponentSchema with the name, size and alignment of every registered component type, and the stable name and fields of those that have been described (see `Ecs::register_description()`).
+    pub fn schema(&self) -> ComponentSchema {
+        let mut components: Vec<ComponentInfo> = self.components.iter().map(|(id, (name, list))| {
+            let layout: std::alloc::Layout = list.read().layout();
+            ComponentInfo {
+                name        : name.to_string(),
+                size        : layout.size(),
+                align       : layout.align(),
+                description : self.descriptions.get(id).cloned(),
+            }
+        }).collect();
+        components.sort_by(|a, b| a.name.cmp(&b.name));
+        ComponentSchema {
+            version : SCHEMA_VERSION,
+            components,
+        }
+    }
+
+    /// Returns whether the given component type has been registered.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to check.
+    /// 
+    /// **Returns**  
+    /// True if it is registered, or false otherwise.
+    #[inline]
+    pub fn is_registered<T: 'static + Component>(&self) -> bool {
+        self.components.contains_key(&ComponentList::<T>::id())
+    }
+
+
+
+    /// Pushes a new entity onto the ECS. Returns the ID of that entity.
+    /// 
+    /// **Returns**  
+    /// The identifier of that entity, as an Entity.
+    /// 
+    /// **Panics**  
+    /// This function panics if the hard limit of the entity quota has been reached (see `Ecs::try_add_entity()`).
+    pub fn add_entity(&self) -> Entity {
+        match self.try_add_entity() {
+            Ok(entity) => entity,
+            Err(err)   => { panic!("Could not add entity: {}", err); },
+        }
+    }
+
+    /// Adds a new entity to the ECS, unless that would exceed the entity quota (see `Ecs::set_entity_quota()`).
+    /// 
+    /// **Returns**  
+    /// The identifier of the new entity, as an Entity.
+    /// 
+    /// **Errors**  
+    /// This function errors if the hard limit of the entity quota has been reached.
+    pub fn try_add_entity(&self) -> Result<Entity, Error> {
+        // Get a lock first
+        let mut entities: RwLockWriteGuard<(u64, EntityHashSet)> = self.entities.write();
+
+        // Flush what has been reserved so far, as those entities go first
+        self.flush_reserved(&mut entities, self.next_entity.load(Ordering::Relaxed));
+        if let Some(limit) = self.entity_quota.hard {
+            if entities.1.len() >= limit { return Err(Error::QuotaExceeded{ what: "entities", limit }); }
+        }
+
+        // Get the next id and insert it (and anything reserved on other threads in the meantime) into the list of active entities
+        let id: Entity = Entity::from_id(self.next_entity.fetch_add(1, Ordering::Relaxed));
+        self.flush_reserved(&mut entities, u64::from(id) + 1);
+        if !entities.1.contains(&id) { return Err(Error::QuotaExceeded{ what: "entities", limit: self.entity_quota.hard.unwrap_or_default() }); }
+        Ok(id)
+    }
+
+    /// Makes the entity with the given ID alive, if it isn't already.
+    /// 
+    /// Entity IDs up to the given one that have not been handed out yet are skipped, so they are never handed out later.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to spawn.
+    /// 
+    /// **Errors**  
+    /// This function errors if the ID has already been handed out by this Ecs (but the entity is no longer alive), as reviving it would also revive any WeakEntity referring to it. It also errors for `Entity::PLACEHOLDER`.
+    fn spawn_with_id(&self, entity: Entity) -> Result<(), Error> {
+        let mut entities: RwLockWriteGuard<(u64, EntityHashSet)> = self.entities.write();
+        if entities.1.contains(&entity) { return Ok(()); }
+        let id: u64 = u64::from(entity);
+        let next: u64 = id.checked_add(1).ok_or(Error::UnavailableEntityId{ entity })?;
+
+        // Flush what has been reserved so far, then make sure the ID has not been used before
+        self.flush_reserved(&mut entities, self.next_entity.load(Ordering::Relaxed));
+        if entities.1.contains(&entity) { return Ok(()); }
+        if id < entities.0 { return Err(Error::UnavailableEntityId{ entity }); }
+        if let Some(limit) = self.entity_quota.hard {
+            if entities.1.len() >= limit { return Err(Error::QuotaExceeded{ what: "entities", limit }); }
+        }
+
+        // Skip past the given ID
+        self.next_entity.fetch_max(next, Ordering::Relaxed);
+        entities.0 = next;
+        entities.1.insert(entity);
+        self.bump_generation();
+        self.record(entity, StructuralOp::Spawn);
+        if let Some(limit) = self.entity_quota.crossed_soft(entities.1.len()) { self.warn_quota("entities", entities.1.len(), limit); }
+        Ok(())
+    }
+
+    /// Reserves a new entity ID without locking the list of entities.
+    /// 
+    /// Note that the reserved entity only becomes alive (i.e., can receive components) after the next call to `Ecs::flush_entities()` (or `Ecs::add_entity()`, which flushes implicitly). To reserve entities from worker threads, use `Ecs::entity_reserver()` instead.
+    /// 
+    /// **Returns**  
+    /// The identifier of the reserved entity, as an Entity.
+    #[inline]
+    pub fn reserve_entity(&self) -> Entity {
+        Entity::from_id(self.next_entity.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Returns a handle that reserves entity IDs like `Ecs::reserve_entity()`, but that can be cloned and sent to worker threads.
+    /// 
+    /// **Returns**  
+    /// A new EntityReserver for this Ecs.
+    #[inline]
+    pub fn entity_reserver(&self) -> EntityReserver {
+        EntityReserver::new(self.next_entity.clone(), self.deferred.clone())
+    }
+
+    /// Makes an entity alive that was reserved by a CommandBuffer (see `CommandBuffer::spawn()`).
+    /// 
+    /// If the hard limit of the entity quota has been reached, the entity is discarded instead.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to spawn.
+    /// 
+    /// **Returns**  
+    /// True if the entity is now alive, or false if it was not reserved by a CommandBuffer (or has already been spawned or discarded) or exceeded the entity quota.
+    pub(crate) fn spawn_deferred(&self, entity: Entity) -> bool {
+        let mut entities: RwLockWriteGuard<(u64, EntityHashSet)> = self.entities.write();
+
+        // Flush what has been reserved so far, as those entities go first
+        self.flush_reserved(&mut entities, self.next_entity.load(Ordering::Relaxed));
+        if !self.deferred.lock().remove(&entity) { return false; }
+        if self.entity_quota.is_full(entities.1.len()) {
+            self.reject_quota("entities", 1, self.entity_quota.hard.unwrap_or_default());
+            return false;
+        }
+
+        // Make it alive
+        entities.1.insert(entity);
+        self.bump_generation();
+        self.record(entity, StructuralOp::Spawn);
+        if let Some(limit) = self.entity_quota.crossed_soft(entities.1.len()) { self.warn_quota("entities", entities.1.len(), limit); }
+        true
+    }
+
+    /// Flushes all entities reserved with `Ecs::reserve_entity()` (or an EntityReserver) into the list of active entities.
+    /// 
+    /// This should be called at a sync point, i.e., once no worker threads are reserving entities anymore.
+    pub fn flush_entities(&self) {
+        // Get a lock first
+        let mut entities: RwLockWriteGuard<(u64, EntityHashSet)> = self.entities.write();
+
+        // Flush everything that has been handed out so far
+        self.flush_reserved(&mut entities, self.next_entity.load(Ordering::Relaxed));
+    }
+
+    /// Inserts all entity IDs that have been handed out but not yet inserted into the list of active entities, except for those reserved by CommandBuffers.
+    /// 
+    /// **Arguments**
+    ///  * `entities`: The (locked) entity data to flush into.
+    ///  * `until`: The first entity ID that should not be flushed.
+    fn flush_reserved(&self, entities: &mut (u64, EntityHashSet), until: u64) {
+        if until <= entities.0 { return; }
+        let deferred: MutexGuard<EntityHashSet> = self.deferred.lock();
+        let (mut n_inserted, mut n_discarded): (usize, usize) = (0, 0);
+        for id in (entities.0..until).filter(|id| !deferred.contains(&Entity::from_id(*id))) {
+            // Discard the entities that would exceed the entity quota
+            if self.entity_quota.is_full(entities.1.len()) {
+                n_discarded += 1;
+                continue;
+            }
+
+            let entity: Entity = Entity::from_id(id);
+            entities.1.insert(entity);
+            self.record(entity, StructuralOp::Spawn);
+            if let Some(limit) = self.entity_quota.crossed_soft(entities.1.len()) { self.warn_quota("entities", entities.1.len(), limit); }
+            n_inserted += 1;
+        }
+        drop(deferred);
+        entities.0 = until;
+
+        // Report what we did
+        if n_inserted > 0 { self.bump_generation(); }
+        if n_discarded > 0 { self.reject_quota("entities", n_discarded, self.entity_quota.hard.unwrap_or_default()); }
+    }
+
+    /// Removes the given entity from the internal list.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to remove.
+    /// 
+    /// **Returns**  
+    /// True if we removed something, or false if that entity did not exist already.
+    pub fn remove_entity(&self, entity: Entity) -> bool {
+        // Remove the entity in question
+        {
+            let mut entities: RwLockWriteGuard<(u64, EntityHashSet)> = self.entities.write();
+            if !entities.1.remove(&entity) { return false; }
+        }
+        self.bump_generation();
+        self.record(entity, StructuralOp::Despawn);
+
+        // Also remove its components from all relevant lists
+        for (_, list) in self.components.values() {
+            // Get a lock on this list and then remove it
+            let mut list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+            list.delete(entity);
+        }
+
+        // Finally, remove any relations that targeted it
+        if !self.relations.is_empty() {
+            let removed: EntityHashSet = std::iter::once(entity).collect();
+            for hooks in self.relations.values() {
+                (hooks.cleanup)(self, &removed);
+            }
+        }
+
+        // Done
+        true
+    }
+
+    /// Returns all entities that currently exist.
+    /// 
+    /// **Returns**  
+    /// A snapshot of the existing entities, in no particular order.
+    #[inline]
+    pub fn entities(&self) -> Vec<Entity> {
+        self.entities.read().1.iter().copied().collect()
+    }
+
+    /// Returns whether the given entity currently exists.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to check.
+    /// 
+    /// **Returns**  
+    /// True if the entity exists, or false if it has been removed (or not yet been flushed).
+    #[inline]
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.read().1.contains(&entity)
+    }
+
+    /// Resolves a WeakEntity to an Entity, but only if it still exists.
+    /// 
+    /// **Arguments**
+    ///  * `weak`: The WeakEntity to resolve.
+    /// 
+    /// **Returns**  
+    /// The Entity it refers to if it still exists, or None otherwise.
+    #[inline]
+    pub fn resolve(&self, weak: WeakEntity) -> Option<Entity> {
+        let entity: Entity = weak.entity_unchecked();
+        if self.is_alive(entity) { Some(entity) } else { None }
+    }
+
+    /// Applies the given CommandBuffers to the ECS.
+    /// 
+    /// First, any reserved entities are flushed (see `Ecs::flush_entities()`). Then, the buffers are applied one after the other in the given order, which should be the same every frame (e.g., the order of the systems that recorded them). Entities spawned by a buffer become alive when its spawn command is applied.
+    /// 
+    /// **Arguments**
+    ///  * `buffers`: The CommandBuffers to apply.
+    pub fn apply_commands(&self, buffers: impl IntoIterator<Item = CommandBuffer>) {
+        // Make any reserved entities alive
+        self.flush_entities();
+
+        // Apply the buffers in order
+        for buffer in buffers {
+            buffer.apply(self);
+        }
+    }
+
+    /// Removes all entities that match the given filter.
+    /// 
+    /// This is more efficient than calling `Ecs::remove_entity()` for every entity, as every component list is locked only once.
+    /// 
+    /// **Arguments**
+    ///  * `filter`: The filter that decides if an entity should be removed. It is called with no locks held, so it may use the Ecs itself to inspect the entity's components.
+    /// 
+    /// **Returns**  
+    /// The number of entities that have been removed.
+    pub fn despawn_where<F: FnMut(Entity) -> bool>(&self, mut filter: F) -> usize {
+        // Collect the entities to remove first, so the filter may access the ECS
+        let to_remove: Vec<Entity> = {
+            let entities: Vec<Entity> = self.entities.read().1.iter().copied().collect();
+            entities.into_iter().filter(|e| filter(*e)).collect()
+        };
+
+        // Remove them from the list of active entities
+        {
+            let mut entities: RwLockWriteGuard<(u64, EntityHashSet)> = self.entities.write();
+            for entity in &to_remove {
+                entities.1.remove(entity);
+                self.record(*entity, StructuralOp::Despawn);
+            }
+        }
+        if !to_remove.is_empty() { self.bump_generation(); }
+
+        // Also remove their components from all relevant lists
+        for (_, list) in self.components.values() {
+            // Get a lock on this list and then remove all of them
+            let mut list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+            for entity in &to_remove {
+                list.delete(*entity);
+            }
+        }
+
+        // Finally, remove any relations that targeted them
+        if !self.relations.is_empty() && !to_remove.is_empty() {
+            let removed: EntityHashSet = to_remove.iter().copied().collect();
+            for hooks in self.relations.values() {
+                (hooks.cleanup)(self, &removed);
+            }
+        }
+
+        // Done
+        to_remove.len()
+    }
+
+    /// Removes all entities and their components from the ECS.
+    /// 
+    /// Registered component types remain registered, and entity IDs are not reused. Any reserved but not yet flushed entities are discarded.
+    pub fn clear(&self) {
+        // Clear the list of active entities
+        {
+            let mut entities: RwLockWriteGuard<(u64, EntityHashSet)> = self.entities.write();
+            for entity in entities.1.drain() { self.record(entity, StructuralOp::Despawn); }
+            // Any reserved entities are discarded as well
+            entities.0 = self.next_entity.load(Ordering::Relaxed);
+            self.deferred.lock().clear();
+        }
+        self.bump_generation();
+
+        // Clear all the lists
+        for (_, list) in self.components.values() {
+            list.write().clear();
+        }
+    }
+
+
+
+    /// Adds the given component to the given entity.  
+    /// Overwrites any existing component.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to add.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to add a component for.
+    ///  * `data`: The data to set the component value to.
+    /// 
+    /// **Returns**  
+    /// 'true' if the component was added, or 'false' otherwise. It can only fail to be added if the Entity does not exist, if the component type has a fixed capacity that has been reached (see `Ecs::register_fixed()`), or if the hard limit of its quota has been reached (see `Ecs::set_component_quota()`).
+    pub fn add_component<T: 'static + Component>(&self, entity: Entity, data: T) -> bool {
+        // Get a read lock on the entity list
+        let entities: RwLockReadGuard<(_, EntityHashSet)> = self.entities.read();
+
+        // Check if the entity exists
+        if !entities.1.contains(&entity) { return false; }
+
+        // Try to get the list to insert it into
+        let (_, list) = self.components.get(&ComponentList::<T>::id())
+            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
+        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+
+        // Validate the component in debug builds
+        #[cfg(debug_assertions)]
+        if let Some(validator) = self.validators.get(&ComponentList::<T>::id()) {
+            if let Err(reason) = validator(&data) { panic!("Invalid {} for entity {}: {}", std::any::type_name::<T>(), entity, reason); }
+        }
+
+        // Check the quota, then perform the insert
+        let mut list: MappedRwLockWriteGuard<ComponentList<T>> = RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T));
+        let quota: Option<&Quota> = self.quotas.get(&ComponentList::<T>::id());
+        let is_new: bool = list.get(entity).is_none();
+        if is_new && quota.map(|q| q.is_full(list.len())).unwrap_or(false) { return false; }
+        if !list.insert(entity, data) { return false; }
+        let count: usize = list.len();
+        drop(list);
+        drop(entities);
+
+        // Done
+        if let Some(limit) = quota.filter(|_| is_new).and_then(|q| q.crossed_soft(count)) { self.warn_quota(std::any::type_name::<T>(), count, limit); }
+        if is_new {
+            self.bump_generation();
+            self.record(entity, StructuralOp::Insert(std::any::type_name::<T>()));
+        }
+        true
+    }
+
+    /// Adds a component to the given entity, reusing a pooled one if possible (see `Ecs::register_pooled()`).
+    /// 
+    /// Pooled components are reset before reuse; if the pool is empty, a default component is used instead.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to add.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to add a component for.
+    ///  * `init`: A closure that initializes the (reset or default) component.
+    /// 
+    /// **Returns**  
+    /// 'true' if the component was added, or 'false' otherwise (see `Ecs::add_component()`).
+    pub fn add_pooled<T: 'static + Poolable>(&self, entity: Entity, init: impl FnOnce(&mut T)) -> bool {
+        // Get a component to reuse
+        let mut component: T = match self.list_component_mut::<T>().take_pooled() {
+            Some(mut component) => { component.reset(); component },
+            None                => T::default(),
+        };
+
+        // Initialize it and add it as usual
+        init(&mut component);
+        self.add_component(entity, component)
+    }
+
+    /// Returns the component of the given Entity.
+    /// 
+    /// The lock returned is actually a lock to the parent ComponentList, so try to keep access to a minimum.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to get.
+    /// 
+    /// **Returns**  
+    /// An immuteable reference to the Component, or else None if the given entity does not exist or does not have such a Component.
+    pub fn get_component<'a, T: 'static + Component>(&'a self, entity: Entity) -> Option<MappedRwLockReadGuard<'a, T>> {
+        // Get a read lock on the entity list
+        let entities: RwLockReadGuard<(_, EntityHashSet)> = self.entities.read();
+
+        // Check if the entity exists
+        if !entities.1.contains(&entity) { return None; }
+
+        // Try to get the list to get from
+        let (_, list) = self.components.get(&ComponentList::<T>::id())
+            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
+        let list: RwLockReadGuard<'a, Box<dyn ComponentListBase>> = list.read();
+
+        // Get the casted version of the list
+        let result: MappedRwLockReadGuard<'a, ComponentList<T>> = RwLockReadGuard::map(list, |l| to_component_list!(l, T));
+
+        // Either return None if it doesn't exist, or else the value in a casted guard
+        if result.get(entity).is_none() { return None }
+        Some(MappedRwLockReadGuard::map(result, |r| r.get(entity).unwrap()))
+    }
+
+    /// Returns the component of the given Entity.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to get.
+    /// 
+    /// **Returns**  
+    /// A muteable reference to the Component, or else None if the given entity does not exist or does not have such a Component.
+    pub fn get_component_mut<'a, T: 'static + Component>(&'a self, entity: Entity) -> Option<MappedRwLockWriteGuard<'a, T>> {
+        // Get a read lock on the entity list
+        let entities: RwLockReadGuard<(_, EntityHashSet)> = self.entities.read();
+
+        // Check if the entity exists
+        if !entities.1.contains(&entity) { return None; }
+
+        // Try to get the list to get from
+        let (_, list) = self.components.get(&ComponentList::<T>::id())
+            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
+        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+
+        // Get the casted version of the list
+        let mut result: MappedRwLockWriteGuard<'a, ComponentList<T>> = RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T));
+
+        // Either return None if it doesn't exist, or else the value in a casted guard
+        if result.get_mut(entity).is_none() { return None }
+        Some(MappedRwLockWriteGuard::map(result, |r| r.get_mut(entity).unwrap()))
+    }
+
+    /// Returns all entities with the given component type.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to list.
+    /// 
+    /// **Returns**  
+    /// An immuteable reference to the list of components.
+    pub fn list_component<T: 'static + Component>(&self) -> MappedRwLockReadGuard<ComponentList<T>> {
+        // Get a read lock on the list in question
+        let (_, list) = self.components.get(&ComponentList::<T>::id())
+            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
+        let list: RwLockReadGuard<Box<dyn ComponentListBase>> = list.read();
+
+        // Return the casted instance of the list
+        RwLockReadGuard::map(list, |l| to_component_list!(l, T))
+    }
+
+    /// Returns all entities with the given component type.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to list.
+    /// 
+    /// **Returns**  
+    /// A muteable reference to the list of components.
+    pub fn list_component_mut<T: 'static + Component>(&self) -> MappedRwLockWriteGuard<ComponentList<T>> {
+        // Get a write lock on the list in question
+        let (_, list) = self.components.get(&ComponentList::<T>::id())
+            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
+        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+
+        // Return the casted instance of the list
+        RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T))
+    }
+
+    /// Calls the given closure for every component of the given type, together with the entity it belongs to.
+    /// 
+    /// The list is locked once, and its dense storage is walked directly. This is faster than iterating over it for tight loops.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to visit.
+    /// 
+    /// **Arguments**
+    ///  * `f`: The closure to call for every (Entity, component) pair. Must not access the list of `T` itself, or it will deadlock.
+    #[inline]
+    pub fn for_each<T: 'static + Component>(&self, f: impl FnMut(Entity, &T)) {
+        self.list_component::<T>().for_each(f);
+    }
+
+    /// Calls the given closure for every component of the given type (as muteable), together with the entity it belongs to.
+    /// 
+    /// The list is locked once, and its dense storage is walked directly. This is faster than iterating over it for tight loops.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to update.
+    /// 
+    /// **Arguments**
+    ///  * `f`: The closure to call for every (Entity, component) pair. Must not access the list of `T` itself, or it will deadlock.
+    #[inline]
+    pub fn for_each_mut<T: 'static + Component>(&self, f: impl FnMut(Entity, &mut T)) {
+        self.list_component_mut::<T>().for_each_mut(f);
+    }
+
+    /// Captures an owned copy of the components of all entities that have all of the given components.
+    /// 
+    /// This is useful for assertions in tests, or to inspect a frame later.
+    /// 
+    /// **Generic Types**
+    ///  * `Q`: The tuple of (cloneable) Component types to capture, e.g. `(Position, Velocity)`.
+    /// 
+    /// **Returns**  
+    /// A QuerySnapshot with the captured components.
+    #[inline]
+    pub fn snapshot<Q: Snapshot>(&self) -> QuerySnapshot<Q> {
+        QuerySnapshot::capture(self)
+    }
+
+    /// Temporarily rewinds the captured components to the values in the given snapshot, runs the given closure and then restores the current values.
+    /// 
+    /// This may be used for lag compensation, where hit detection must run against the state the client saw at some past tick. Only the component types in the snapshot are rewound, so keep one snapshot per tick of just the types that matter (e.g., transforms and hitboxes).
+    /// 
+    /// Entities that no longer have a captured component are left alone, and entities that did not match when the snapshot was taken are not rewound at all.
+    /// 
+    /// **Generic Types**
+    ///  * `Q`: The tuple of Component types in the snapshot.
+    ///  * `R`: The result of the closure.
+    /// 
+    /// **Arguments**
+    ///  * `snapshot`: The QuerySnapshot to rewind to.
+    ///  * `f`: The closure to run while rewound. Any changes it makes to the rewound components are discarded when they are restored.
+    /// 
+    /// **Returns**  
+    /// The result of the closure.
+    pub fn rewind<Q: Snapshot + Clone, R>(&self, snapshot: &QuerySnapshot<Q>, f: impl FnOnce(&Ecs) -> R) -> R {
+        let mut entries: Vec<(Entity, Q)> = snapshot.iter().map(|(entity, values)| (entity, values.clone())).collect();
+        let mask: Vec<u8> = Q::swap(self, &mut entries, None);
+
+        // Restore the current values when done (or when the closure panics)
+        let _guard: RewindGuard<Q> = RewindGuard{ ecs: self, entries, mask };
+        f(self)
+    }
+
+    /// Locks the lists of several component types at once, and runs the given closure with plain references to them.
+    /// 
+    /// The access set is checked once, and the lists are always locked in the same (canonical) order regardless of the order in which they are given, so two splits cannot deadlock each other. Inside the closure, the lists are accessed without any further locking, which makes this the fastest way to write tight loops over multiple component types.
+    /// 
+    /// # Example
+    /// ```ignore
+    /// ecs.split::<(Write<Position>, Read<Velocity>), _>(|(positions, velocities)| {
+    ///     positions.for_each_mut(|entity, pos| { if let Some(vel) = velocities.get(entity) { pos.0 += vel.0; } });
+    /// })?;
+    /// ```
+    /// 
+    /// **Generic Types**
+    ///  * `S`: The tuple of `Read<T>` and `Write<T>` accesses, e.g. `(Write<Position>, Read<Velocity>)`.
+    ///  * `R`: The result of the closure.
+    /// 
+    /// **Arguments**
+    ///  * `f`: The closure to run, which gets a tuple of `&ComponentList<T>` (for reads) and `&mut ComponentList<T>` (for writes) in the order of the access set. Must not access the locked lists through the Ecs, or it will deadlock.
+    /// 
+    /// **Returns**  
+    /// The result of the closure.
+    /// 
+    /// **Errors**  
+    /// This function errors if a component type is accessed more than once, or if it has not been registered.
+    pub fn split<S: AccessSet, R>(&self, f: impl FnOnce(S::Items<'_>) -> R) -> Result<R, Error> {
+        // Check the access set
+        let accesses: Vec<(TypeId, &'static str, bool)> = S::accesses();
+        for (i, (id, name, _)) in accesses.iter().enumerate() {
+            if accesses[..i].iter().any(|(other, _, _)| other == id) { return Err(Error::DuplicateAccess{ name }); }
+            if !self.components.contains_key(id) { return Err(Error::UnregisteredComponent{ id: *id }); }
+        }
+
+        // Lock the lists in order of TypeId, but store them in order of the access set
+        let mut order: Vec<usize> = (0..accesses.len()).collect();
+        order.sort_by_key(|i| accesses[*i].0);
+        let mut locks: Vec<Option<LockedList>> = (0..accesses.len()).map(|_| None).collect();
+        for i in order {
+            let (id, _, write): (TypeId, &'static str, bool) = accesses[i];
+            let list: &RwLock<Box<dyn ComponentListBase>> = &self.components[&id].1;
+            locks[i] = Some(if write { LockedList::Write(list.write()) } else { LockedList::Read(list.read()) });
+        }
+        let mut locks: Vec<LockedList> = locks.into_iter().flatten().collect();
+
+        // Run the closure
+        Ok(f(S::fetch(&mut locks)))
+    }
+
+    /// Copies the entities that match the given filter, with only the given component types, into another Ecs.
+    /// 
+    /// This is the general mechanism behind extracting render data, building per-client snapshots or isolating a selection in an editor.
+    /// 
+    /// **Generic Types**
+    ///  * `E`: The tuple of (cloneable) Component types to copy, e.g. `(Transform, Mesh)`.
+    /// 
+    /// **Arguments**
+    ///  * `target`: The Ecs to copy to. Must be a different Ecs than this one, and have all types in `E` registered. Components are added through it as usual, so its quotas, validators and journal apply.
+    ///  * `ids`: Whether to keep the IDs of the copied entities, or spawn them as new entities in the target.
+    ///  * `filter`: Decides which entities to copy. It is called without any component lists locked.
+    /// 
+    /// **Returns**  
+    /// The mapping from every copied entity to its entity in the target.
+    /// 
+    /// **Errors**  
+    /// This function errors if the target is this Ecs, or if one of the component types is not registered in the target; nothing is copied in those cases. It also errors if the target refuses to spawn an entity (e.g., because of its entity quota, or because a preserved ID has been used and removed in the target before) or to add a component (see `Ecs::add_component()`), in which case everything up to that point has been copied.
+    pub fn extract_filtered<E: Extract>(&self, target: &Ecs, ids: ExtractIds, mut filter: impl FnMut(Entity) -> bool) -> Result<EntityMapping, Error> {
+        if std::ptr::eq(self, target) { return Err(Error::ExtractIntoSelf); }
+        if let Some(id) = E::unregistered(target) { return Err(Error::UnregisteredComponent{ id }); }
+
+        // Find the entities to copy, in a deterministic order
+        let mut entities: Vec<Entity> = self.entities();
+        entities.sort();
+        entities.retain(|entity| filter(*entity));
+
+        // Decide on their IDs in the target
+        let mapping: Vec<(Entity, Entity)> = entities.into_iter().map(|entity| match ids {
+            ExtractIds::Preserve => target.spawn_with_id(entity).map(|_| (entity, entity)),
+            ExtractIds::Remap    => target.try_add_entity().map(|new| (entity, new)),
+        }).collect::<Result<_, Error>>()?;
+
+        // Copy the components
+        E::copy(self, target, &mapping)?;
+        Ok(mapping.into_iter().collect())
+    }
+
+    /// Computes a deterministic hash of the entities and the given component types.
+    /// 
+    /// Two Ecs's with the same entities and components have the same hash, on any machine. Lockstep multiplayer or replay verification can compare it every tick to detect desyncs early, and `StateHash::diverging()` then tells which component types differ.
+    /// 
+    /// **Generic Types**
+    ///  * `H`: The tuple of Component types to hash, which must implement `WireCodec` (e.g., `(Position, Health)`).
+    /// 
+    /// **Returns**  
+    /// The StateHash, with a sub-hash for the entities and every component type.
+    #[inline]
+    pub fn state_hash<H: HashedComponents>(&self) -> StateHash {
+        StateHash::compute::<H>(self)
+    }
+
+    /// Limits the number of entities in the Ecs.
+    /// 
+    /// Exceeding the soft limit sends a `QuotaWarning` (if an `Events<QuotaWarning>` resource exists), and `Ecs::try_add_entity()` refuses to exceed the hard limit. Reserved entities are checked when they are flushed, and entities spawned by CommandBuffers when their buffer is applied: those that would exceed the hard limit are discarded (which sends a `QuotaRejection`, if an `Events<QuotaRejection>` resource exists), so any commands for them do nothing.
+    /// 
+    /// **Arguments**
+    ///  * `quota`: The new Quota for entities.
+    #[inline]
+    pub fn set_entity_quota(&mut self, quota: Quota) {
+        self.entity_quota = quota;
+    }
+
+    /// Limits the number of components of the given type in the Ecs.
+    /// 
+    /// Exceeding the soft limit sends a `QuotaWarning` (if an `Events<QuotaWarning>` resource exists), and `Ecs::add_component()` refuses to exceed the hard limit.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to limit.
+    /// 
+    /// **Arguments**
+    ///  * `quota`: The new Quota for the component type.
+    #[inline]
+    pub fn set_component_quota<T: 'static + Component>(&mut self, quota: Quota) {
+        self.quotas.insert(ComponentList::<T>::id(), quota);
+    }
+
+    /// Sends a QuotaWarning, if there is an event queue for them.
+    /// 
+    /// **Arguments**
+    ///  * `what`: What exceeded its quota.
+    ///  * `count`: The number of entities or components after the quota was exceeded.
+    ///  * `limit`: The soft limit that was exceeded.
+    fn warn_quota(&self, what: &'static str, count: usize, limit: usize) {
+        debug!("Soft quota of {} {} exceeded", limit, what);
+        if let Some(mut events) = self.resource_mut::<Events<QuotaWarning>>() {
+            events.send(QuotaWarning{ what, count, limit });
+        }
+    }
+
+    /// Sends a QuotaRejection, if there is an event queue for them.
+    /// 
+    /// **Arguments**
+    ///  * `what`: What exceeded its quota.
+    ///  * `discarded`: The number of items that were discarded.
+    ///  * `limit`: The hard limit that would have been exceeded.
+    fn reject_quota(&self, what: &'static str, discarded: usize, limit: usize) {
+        debug!("Hard quota of {} {} exceeded; discarded {}", limit, what, discarded);
+        if let Some(mut events) = self.resource_mut::<Events<QuotaRejection>>() {
+            events.send(QuotaRejection{ what, discarded, limit });
+        }
+    }
+
+    /// Returns a read-only view on the Ecs.
+    /// 
+    /// Pass this to code that should only read the world (e.g., rendering extraction or debug overlays), so that its signature shows it cannot change anything.
+    /// 
+    /// **Returns**  
+    /// A WorldView that only exposes the non-mutating functions of the Ecs.
+    #[inline]
+    pub fn view(&self) -> WorldView<'_> {
+        WorldView::new(self)
+    }
+
+    /// Returns the type-erased list of the given component type.
+    /// 
+    /// **Arguments**
+    ///  * `id`: The TypeId of the Component type.
+    /// 
+    /// **Returns**  
+    /// An immuteable reference to the list of components, or None if the component type is not registered.
+    #[inline]
+    pub(crate) fn list_component_dyn(&self, id: TypeId) -> Option<RwLockReadGuard<'_, Box<dyn ComponentListBase>>> {
+        self.components.get(&id).map(|(_, list)| list.read())
+    }
+
+    /// Removes the components of the given type from all entities.
+    /// 
+    /// The component type itself remains registered.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to remove.
+    /// 
+    /// **Returns**  
+    /// The number of components that were removed.
+    pub fn clear_component<T: 'static + Component>(&self) -> usize {
+        // Get a write lock on the list in question
+        let mut list: MappedRwLockWriteGuard<ComponentList<T>> = self.list_component_mut::<T>();
+
+        // Clear it
+        let n_components: usize = list.len();
+        for (entity, _) in list.iter_entities() { self.record(entity, StructuralOp::Remove(std::any::type_name::<T>())); }
+        list.clear();
+        if n_components > 0 { self.bump_generation(); }
+        n_components
+    }
+
+    /// Returns the component of the given Entity, but only if that can be done without blocking.
+    /// 
+    /// This is useful for opportunistic systems (debug overlays, screenshots) that should never stall the frame.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to get.
+    /// 
+    /// **Returns**  
+    /// An immuteable reference to the Component, or else None if the given entity does not exist or does not have such a Component.
+    /// 
+    /// **Errors**  
+    /// This function errors with `Error::WouldBlock` if either the entity list or the component list is currently locked for writing.
+    pub fn try_get_component<'a, T: 'static + Component>(&'a self, entity: Entity) -> Result<Option<MappedRwLockReadGuard<'a, T>>, Error> {
+        // Try to get a read lock on the entity list
+        let entities: RwLockReadGuard<(_, EntityHashSet)> = match self.entities.try_read() {
+            Some(entities) => entities,
+            None           => { return Err(Error::WouldBlock{ what: "entities" }); },
+        };
+
+        // Check if the entity exists
+        if !entities.1.contains(&entity) { return Ok(None); }
+
+        // Try to get the list to get from
+        let result: MappedRwLockReadGuard<'a, ComponentList<T>> = self.try_list_component::<T>()?;
+
+        // Either return None if it doesn't exist, or else the value in a casted guard
+        if result.get(entity).is_none() { return Ok(None) }
+        Ok(Some(MappedRwLockReadGuard::map(result, |r| r.get(entity).unwrap())))
+    }
+
+    /// Returns the component of the given Entity, but only if that can be done without blocking.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to get.
+    /// 
+    /// **Returns**  
+    /// A muteable reference to the Component, or else None if the given entity does not exist or does not have such a Component.
+    /// 
+    /// **Errors**  
+    /// This function errors with `Error::WouldBlock` if the entity list is currently locked for writing or the component list is locked at all.
+    pub fn try_get_component_mut<'a, T: 'static + Component>(&'a self, entity: Entity) -> Result<Option<MappedRwLockWriteGuard<'a, T>>, Error> {
+        // Try to get a read lock on the entity list
+        let entities: RwLockReadGuard<(_, EntityHashSet)> = match self.entities.try_read() {
+            Some(entities) => entities,
+            None           => { return Err(Error::WouldBlock{ what: "entities" }); },
+        };
+
+        // Check if the entity exists
+        if !entities.1.contains(&entity) { return Ok(None); }
+
+        // Try to get the list to get from
+        let mut result: MappedRwLockWriteGuard<'a, ComponentList<T>> = self.try_list_component_mut::<T>()?;
+
+        // Either return None if it doesn't exist, or else the value in a casted guard
+        if result.get_mut(entity).is_none() { return Ok(None) }
+        Ok(Some(MappedRwLockWriteGuard::map(result, |r| r.get_mut(entity).unwrap())))
+    }
+
+    /// Returns all entities with the given component type, but only if that can be done without blocking.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to list.
+    /// 
+    /// **Returns**  
+    /// An immuteable reference to the list of components.
+    /// 
+    /// **Errors**  
+    /// This function errors with `Error::WouldBlock` if the list is currently locked for writing.
+    pub fn try_list_component<T: 'static + Component>(&self) -> Result<MappedRwLockReadGuard<'_, ComponentList<T>>, Error> {
+        // Try to get a read lock on the list in question
+        let (_, list) = self.components.get(&ComponentList::<T>::id())
+            .unwrap_or_else(|| panic!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
+        let list: RwLockReadGuard<Box<dyn ComponentListBase>> = match list.try_read() {
+            Some(list) => list,
+            None       => { return Err(Error::WouldBlock{ what: ComponentList::<T>::type_name() }); },
+        };
+
+        // Return the casted instance of the list
+        Ok(RwLockReadGuard::map(list, |l| to_component_list!(l, T)))
+    }
+
+    /// Returns all entities with the given component type, but only if that can be done without blocking.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to list.
+    /// 
+    /// **Returns**  
+    /// A muteable reference to the list of components.
+    /// 
+    /// **Errors**  
+    /// This function errors with `Error::WouldBlock` if the list is currently locked (either for reading or writing).
+    pub fn try_list_component_mut<T: 'static + Component>(&self) -> Result<MappedRwLockWriteGuard<'_, ComponentList<T>>, Error> {
+        // Try to get a write lock on the list in question
+        let (_, list) = self.components.get(&ComponentList::<T>::id())
+            .unwrap_or_else(|| panic!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
+        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = match list.try_write() {
+            Some(list) => list,
+            None       => { return Err(Error::WouldBlock{ what: ComponentList::<T>::type_name() }); },
+        };
+
+        // Return the casted instance of the list
+        Ok(RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T)))
+    }
+
+    /// Removes a component for the given entity.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to remove.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to remove the component of.
+    /// 
+    /// **Returns**  
+    /// Returns the removed component if it existed, or else None.
+    #[inline]
+    pub fn remove_component<T: 'static + Component>(&self, entity: Entity) -> Option<T> {
+        // Get a write lock on the list in question
+        let (_, list) = self.components.get(&ComponentList::<T>::id())
+            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
+        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+
+        // Remove it
+        let result: Option<T> = RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T)).remove(entity);
+        if result.is_some() {
+            self.bump_generation();
+            self.record(entity, StructuralOp::Remove(std::any::type_name::<T>()));
+        }
+        result
+    }
+
+    /// Swaps the components of the given type between two entities.
+    /// 
+    /// No data is moved or cloned in the process. If only one of the entities has such a component, it is moved to the other.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to swap.
+    /// 
+    /// **Arguments**
+    ///  * `a`: The first Entity to swap the component of.
+    ///  * `b`: The second Entity to swap the component of.
+    /// 
+    /// **Returns**  
+    /// True if the components have been swapped, or false if either entity does not exist or neither of them has such a component.
+    pub fn swap_component<T: 'static + Component>(&self, a: Entity, b: Entity) -> bool {
+        // Get a read lock on the entity list
+        let entities: RwLockReadGuard<(_, EntityHashSet)> = self.entities.read();
+
+        // Check if the entities exist
+        if !entities.1.contains(&a) || !entities.1.contains(&b) { return false; }
+
+        // Perform the swap
+        let mut list: MappedRwLockWriteGuard<ComponentList<T>> = self.list_component_mut::<T>();
+        let (had_a, had_b): (bool, bool) = (list.get(a).is_some(), list.get(b).is_some());
+        let swapped: bool = list.swap(a, b);
+        drop(list);
+        if swapped { self.bump_generation(); }
+
+        // If only one of them had the component, it moved from one to the other
+        if had_a != had_b {
+            let (from, to): (Entity, Entity) = if had_a { (a, b) } else { (b, a) };
+            self.record(from, StructuralOp::Remove(std::any::type_name::<T>()));
+            self.record(to, StructuralOp::Insert(std::any::type_name::<T>()));
+        }
+        swapped
+    }
+
+    /// Verifies the invariants that span multiple internal structures of the Ecs.
+    /// 
+    /// That is, it checks that every component belongs to a live entity, that the internal mappings of every component list are consistent, that every relation targets a live entity and that the hierarchy (if registered) has no cycles. Violating any of these is a bug (in the Ecs or in code that bypasses it), so this is meant for tests and for checking the world after loading a save.
+    /// 
+    /// **Returns**  
+    /// One human-readable line per violation, or an empty list if the world is intact.
+    pub fn check_integrity(&self) -> Vec<String> {
+        let mut result: Vec<String> = vec![];
+
+        // Check the component lists
+        for (name, list) in self.components.values() {
+            if !list.read().is_consistent() { result.push(format!("{}: inconsistent component list", name)); }
+        }
+        for (entity, name) in self.find_orphans() {
+            result.push(format!("entity {}: {} belongs to a dead entity", entity, name));
+        }
+
+        // Check the relations
+        for (id, hooks) in &self.relations {
+            for (source, target) in (hooks.dangling)(self) {
+                result.push(format!("entity {}: {} targets dead entity {}", source, self.components[id].0, target));
+            }
+        }
+
+        // Check the hierarchy for cycles (i.e., entities that run into an ancestor twice when walking up)
+        if self.is_registered::<Relation<ChildOf>>() {
+            let list: MappedRwLockReadGuard<ComponentList<Relation<ChildOf>>> = self.list_component::<Relation<ChildOf>>();
+            for (entity, _) in list.iter_entities() {
+                let mut visited: EntityHashSet = EntityHashSet::default();
+                let mut current: Entity = entity;
+                while let Some(relation) = list.get(current) {
+                    if !visited.insert(current) { result.push(format!("entity {}: hierarchy contains a cycle", entity)); break; }
+                    current = relation.target();
+                }
+            }
+        }
+
+        // Done
+        result.sort();
+        result
+    }
+
+    /// Finds all orphaned components, i.e., components whose entity no longer exists.
+    /// 
+    /// These cannot appear through the Ecs itself (removing an entity removes its components), but may be left behind by code that bypasses it.
+    /// 
+    /// **Returns**  
+    /// A list of `(entity, component type name)` pairs, one for every orphaned component.
+    pub fn find_orphans(&self) -> Vec<(Entity, &'static str)> {
+        let entities: RwLockReadGuard<(u64, EntityHashSet)> = self.entities.read();
+        let mut result: Vec<(Entity, &'static str)> = vec![];
+        for (name, list) in self.components.values() {
+            let list: RwLockReadGuard<Box<dyn ComponentListBase>> = list.read();
+            result.extend((0..list.len()).filter_map(|i| list.get_entity(i)).filter(|e| !entities.1.contains(e)).map(|e| (e, *name)));
+        }
+        result
+    }
+
+    /// Finds all orphaned components (see `Ecs::find_orphans()`) and reports and/or removes them.
+    /// 
+    /// Every orphan is reported as an `OrphanedComponent` event (if an `Events<OrphanedComponent>` resource exists). Run this as a maintenance system (e.g., once every few seconds), or after loading a save.
+    /// 
+    /// **Arguments**
+    ///  * `policy`: Whether to only report the orphans, or to remove them as well.
+    /// 
+    /// **Returns**  
+    /// The number of orphaned components found.
+    pub fn clean_orphans(&self, policy: OrphanPolicy) -> usize {
+        let orphans: Vec<(Entity, &'static str)> = self.find_orphans();
+        if orphans.is_empty() { return 0; }
+        debug!("Found {} orphaned components", orphans.len());
+
+        // Remove them if told to do so
+        let removed: bool = policy == OrphanPolicy::Remove;
+        if removed {
+            for (_, list) in self.components.values() {
+                let mut list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+                for (entity, _) in &orphans {
+                    list.delete(*entity);
+                }
+            }
+            for (entity, component) in &orphans { self.record(*entity, StructuralOp::Remove(component)); }
+            self.bump_generation();
+        }
+
+        // Report them
+        if let Some(mut events) = self.resource_mut::<Events<OrphanedComponent>>() {
+            for (entity, component) in &orphans {
+                events.send(OrphanedComponent{ entity: *entity, component, removed });
+            }
+        }
+        orphans.len()
+    }
+
+    /// Validates all components of the types registered with `Ecs::register_validator()`.
+    /// 
+    /// Call this after loading a save or receiving a snapshot, or at the end of a frame in debug builds to catch values that became invalid through mutation.
+    /// 
+    /// **Returns**  
+    /// One human-readable line per invalid component, or an empty list if they are all valid.
+    pub fn validate_components(&self) -> Vec<String> {
+        let mut result: Vec<String> = vec![];
+        for (id, validator) in &self.validators {
+            let (name, list) = &self.components[id];
+            let list: RwLockReadGuard<Box<dyn ComponentListBase>> = list.read();
+            for entity in (0..list.len()).filter_map(|i| list.get_entity(i)) {
+                if let Err(reason) = list.get_any(entity).map(validator).unwrap_or(Ok(())) {
+                    result.push(format!("entity {}: invalid {}: {}", entity, name, reason));
+                }
+            }
+        }
+        result.sort();
+        result
+    }
+
+    /// Stores the current value of every double-buffered component (see `Ecs::register_previous()`) as its previous value.
+    /// 
+    /// This should be called at the start of every fixed tick, before its systems change anything.
+    pub fn store_previous(&self) {
+        for hook in self.previous.values() {
+            hook(self);
+        }
+    }
+
+    /// Returns the value that the component of the given entity had at the last call to `Ecs::store_previous()`.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type we want to get. Must have been registered with `Ecs::register_previous()`.
+    /// 
+    /// **Returns**  
+    /// An immuteable reference to the previous value, or else None if the given entity does not exist or did not have such a Component at that time.
+    #[inline]
+    pub fn get_previous<T: 'static + Component>(&self, entity: Entity) -> Option<MappedRwLockReadGuard<'_, T>> {
+        self.get_component::<Previous<T>>(entity).map(|p| MappedRwLockReadGuard::map(p, |p| &p.0))
+    }
+
+    /// Creates a relation of the given kind from one entity to another.  
+    /// Because an entity can have only one relation of each kind, this overwrites any existing relation of that kind.
+    /// 
+    /// Hierarchy relations (`ChildOf`) are refused if they would introduce a cycle, just like in `Ecs::set_parent()`.
+    /// 
+    /// **Generic Types**
+    ///  * `R`: The kind of relation to create. Must have been registered with `Ecs::register_relation()`.
+    /// 
+    /// **Arguments**
+    ///  * `source`: The Entity that will have the relation.
+    ///  * `target`: The Entity that the relation will point to.
+    /// 
+    /// **Returns**  
+    /// True if the relation was created, or false if either of the entities does not exist or if it would introduce a cycle in the hierarchy.
+    pub fn relate<R: 'static>(&self, source: Entity, target: Entity) -> bool {
+        // Make sure the target exists before adding a relation to it
+        let entities: RwLockReadGuard<(_, EntityHashSet)> = self.entities.read();
+        if !entities.1.contains(&target) { return false; }
+        drop(entities);
+
+        // Make sure we do not introduce a cycle in the hierarchy
+        if TypeId::of::<R>() == TypeId::of::<ChildOf>() && (source == target || self.ancestors(target).any(|a| a == source)) { return false; }
+
+        // Add the relation as a normal component
+        self.add_component(source, Relation::<R>::new(target))
+    }
+
+    /// Removes the relation of the given kind from the given entity.
+    /// 
+    /// **Generic Types**
+    ///  * `R`: The kind of relation to remove.
+    /// 
+    /// **Arguments**
+    ///  * `source`: The Entity to remove the relation of.
+    /// 
+    /// **Returns**  
+    /// The target of the removed relation if there was one, or else None.
+    #[inline]
+    pub fn unrelate<R: 'static>(&self, source: Entity) -> Option<Entity> {
+        self.remove_component::<Relation<R>>(source).map(|r| r.target())
+    }
+
+    /// Returns the target of the relation of the given kind of the given entity.
+    /// 
+    /// **Generic Types**
+    ///  * `R`: The kind of relation to get.
+    /// 
+    /// **Arguments**
+    ///  * `source`: The Entity to get the relation of.
+    /// 
+    /// **Returns**  
+    /// The target of the relation if the entity has one, or else None.
+    #[inline]
+    pub fn related<R: 'static>(&self, source: Entity) -> Option<Entity> {
+        self.list_component::<Relation<R>>().get(source).map(|r| r.target())
+    }
+
+    /// Returns all entities that have a relation of the given kind to the given entity.
+    /// 
+    /// Note that this iterates over all relations of that kind.
+    /// 
+    /// **Generic Types**
+    ///  * `R`: The kind of relation to search for.
+    /// 
+    /// **Arguments**
+    ///  * `target`: The Entity to find the relations to.
+    /// 
+    /// **Returns**  
+    /// A list of all entities that target the given entity.
+    pub fn targeting<R: 'static>(&self, target: Entity) -> Vec<Entity> {
+        self.list_component::<Relation<R>>().iter_entities().filter(|(_, r)| r.target() == target).map(|(e, _)| e).collect()
+    }
+
+    /// Sets the parent of the given entity in the hierarchy, replacing any previous parent.
+    /// 
+    /// Note that removing a parent does not remove its children; instead, they will become roots of the hierarchy.
+    /// 
+    /// **Arguments**
+    ///  * `child`: The Entity to set the parent of.
+    ///  * `parent`: The Entity that will be the new parent.
+    /// 
+    /// **Returns**  
+    /// True if the parent was set, or false if either entity does not exist or if it would introduce a cycle in the hierarchy.
+    #[inline]
+    pub fn set_parent(&self, child: Entity, parent: Entity) -> bool {
+        // `relate()` refuses cycles for us
+        self.relate::<ChildOf>(child, parent)
+    }
+
+    /// Removes the given entity from its parent, making it a root of the hierarchy.
+    /// 
+    /// **Arguments**
+    ///  * `child`: The Entity to remove the parent of.
+    /// 
+    /// **Returns**  
+    /// The previous parent if there was one, or else None.
+    #[inline]
+    pub fn remove_parent(&self, child: Entity) -> Option<Entity> {
+        self.unrelate::<ChildOf>(child)
+    }
+
+    /// Returns the parent of the given entity in the hierarchy.
+    /// 
+    /// **Arguments**
+    ///  * `child`: The Entity to get the parent of.
+    /// 
+    /// **Returns**  
+    /// The parent if the entity has one, or else None.
+    #[inline]
+    pub fn parent(&self, child: Entity) -> Option<Entity> {
+        self.related::<ChildOf>(child)
+    }
 
-impl Ecs {
-    /// Constructor for the ECS.
+    /// Returns the children of the given entity in the hierarchy, in order of their ID.
     /// 
     /// **Arguments**
-    ///  * `initial_capacity`: The initial size of the internal vector (might be used to optimize)
+    ///  * `parent`: The Entity to get the children of.
     /// 
-    /// # Returns
-    /// A new instance of the Ecs, already wrapped in an Rc + RefCell.
-    pub fn new(initial_capacity: usize) -> Rc<RefCell<Self>> {
-        debug!("Initialized Entity Component System v{}", env!("CARGO_PKG_VERSION"));
-        Rc::new(RefCell::new(Ecs {
-            entities   : RwLock::new((0, HashSet::with_capacity(initial_capacity))),
-            components : HashMap::with_capacity(16),
-        }))
+    /// **Returns**  
+    /// A list of the entity's (direct) children.
+    pub fn children(&self, parent: Entity) -> Vec<Entity> {
+        let mut children: Vec<Entity> = self.targeting::<ChildOf>(parent);
+        children.sort();
+        children
+    }
+
+    /// Returns an iterator over the ancestors of the given entity, starting with its parent and ending with the root of the hierarchy.
+    /// 
+    /// Note that the hierarchy remains locked for reading while the iterator lives.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to get the ancestors of.
+    /// 
+    /// **Returns**  
+    /// An iterator over the ancestors.
+    #[inline]
+    pub fn ancestors(&self, entity: Entity) -> Ancestors<'_> {
+        Ancestors::new(self.list_component::<Relation<ChildOf>>(), entity)
     }
 
+    /// Returns an iterator over all the descendants of the given entity (excluding itself).
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to get the descendants of.
+    ///  * `order`: Whether to traverse the hierarchy depth-first or breadth-first.
+    /// 
+    /// **Returns**  
+    /// An iterator over the descendants.
+    #[inline]
+    pub fn descendants(&self, entity: Entity, order: TraversalOrder) -> Descendants {
+        Descendants::new(&self.list_component::<Relation<ChildOf>>(), entity, order)
+    }
 
+    /// Returns an iterator over the siblings of the given entity (i.e., the other children of its parent).
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to get the siblings of.
+    /// 
+    /// **Returns**  
+    /// An iterator over the siblings, which is empty if the entity has no parent.
+    pub fn siblings(&self, entity: Entity) -> impl Iterator<Item = Entity> {
+        let siblings: Vec<Entity> = match self.parent(entity) {
+            Some(parent) => self.children(parent),
+            None         => vec![],
+        };
+        siblings.into_iter().filter(move |e| *e != entity)
+    }
 
-    /// Registers a new component type in the ECS.
+    /// Removes multiple components for the given entity.
     /// 
     /// **Generic Types**
-    ///  * `T`: The new Component type to register.
+    ///  * `B`: The Bundle (tuple) of Component types we want to remove.
     /// 
-    /// # Arguments
-    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
-    pub fn register<T: 'static + Component>(this: &Rc<RefCell<Self>>) {
-        // Get the muteable reference
-        let mut mthis: RefMut<Self> = this.borrow_mut();
+    /// **Arguments**
+    ///  * `entity`: The Entity to remove the components of.
+    /// 
+    /// **Returns**  
+    /// A tuple with, for every Component type, the removed component if it existed or else None.
+    #[inline]
+    pub fn remove_components<B: Bundle>(&self, entity: Entity) -> B::Removed {
+        B::remove(self, entity)
+    }
 
-        // Insert the new component type if it does not exist yet
-        if mthis.components.contains_key(&ComponentList::<T>::id()) { panic!("A component with ID {:?} already exists", ComponentList::<T>::id()); }
-        mthis.components.insert(ComponentList::<T>::id(), (
-            std::any::type_name::<T>(),
-            RwLock::new(Box::new(ComponentList::<T>::default())),
-        ));
+    /// Removes the given entity, returning (some of) its components as a Bundle.
+    /// 
+    /// This is useful for patterns where the entity is consumed but (some of) its data lives on, like picking up an item into an inventory.
+    /// 
+    /// **Generic Types**
+    ///  * `B`: The Bundle (tuple) of Component types we want to take.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to consume.
+    /// 
+    /// **Returns**  
+    /// The components of the entity, or None if the entity does not exist, does not have all of the components or if a Component type occurs more than once in the Bundle (in which case it is left untouched).
+    pub fn take<B: Bundle>(&self, entity: Entity) -> Option<B> {
+        // Check if the entity exists
+        if !self.entities.read().1.contains(&entity) { return None; }
 
-        // Also log the new component registration, but only if compiled with log support
-        debug!("Registered new Component type '{:?}'", ComponentList::<T>::id());
+        // Take the components, and then remove the rest of the entity
+        let bundle: B = B::take(self, entity)?;
+        self.remove_entity(entity);
+        Some(bundle)
     }
 
 
 
-    /// Pushes a new entity onto the ECS. Returns the ID of that entity.
+    /// Registers a new one-shot system, which may be run on demand later using `Ecs::run_system()` or `Ecs::run_system_with_input()`.
+    /// 
+    /// This is useful for things like console commands, UI callbacks or scripted triggers.
+    /// 
+    /// **Generic Types**
+    ///  * `I`: The type of the input the system takes (use `()` for none).
+    ///  * `O`: The type of the output the system returns (use `()` for none).
+    /// 
+    /// **Arguments**
+    ///  * `system`: The system to register, as a closure that takes the Ecs and its input.
     /// 
     /// **Returns**  
-    /// The identifier of that entity, as an Entity.
-    pub fn add_entity(&self) -> Entity {
-        // Get a lock first
-        let mut entities: RwLockWriteGuard<(u64, HashSet<_>)> = self.entities.write();
+    /// A SystemId that can be used to run the system.
+    pub fn register_system<I: 'static, O: 'static>(&self, mut system: impl 'static + FnMut(&Ecs, I) -> O) -> SystemId<I, O> {
+        // Erase the types of the system
+        let system: BoxedSystem = Box::new(move |ecs: &Ecs, input: Box<dyn Any>| -> Box<dyn Any> {
+            let input: I = *input.downcast::<I>().expect("Could not downcast system input to its own type");
+            Box::new(system(ecs, input))
+        });
 
-        // Get the next id
-        let id: Entity = entities.0.into();
-        entities.0 += 1;
-        // Insert it into the list of active entities
-        entities.1.insert(id);
+        // Store it
+        let mut systems = self.systems.lock();
+        let id: u64 = systems.0;
+        systems.0 += 1;
+        systems.1.insert(id, Some(system));
 
         // Done
-        id
+        debug!("Registered new one-shot system {}", id);
+        SystemId::new(id)
     }
 
-    /// Removes the given entity from the internal list.
+    /// Removes a one-shot system from the ECS.
     /// 
     /// **Arguments**
-    ///  * `entity`: The Entity to remove.
+    ///  * `id`: The ID of the system to remove.
     /// 
     /// **Returns**  
-    /// True if we removed something, or false if that entity did not exist already.
-    pub fn remove_entity(&self, entity: Entity) -> bool {
-        // Remove the entity in question
-        {
-            let mut entities: RwLockWriteGuard<(u64, HashSet<_>)> = self.entities.write();
-            if !entities.1.remove(&entity) { return false; }
-        }
+    /// True if the system was removed, or false if it did not exist.
+    #[inline]
+    pub fn remove_system<I, O>(&self, id: SystemId<I, O>) -> bool {
+        self.systems.lock().1.remove(&id.id()).is_some()
+    }
 
-        // Also remove its components from all relevant lists
-        for (_, list) in self.components.values() {
-            // Get a lock on this list and then remove it
-            let mut list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
-            list.delete(entity);
-        }
+    /// Runs a one-shot system that takes no input.
+    /// 
+    /// **Arguments**
+    ///  * `id`: The ID of the system to run.
+    /// 
+    /// **Returns**  
+    /// The output of the system.
+    /// 
+    /// **Errors**  
+    /// This function errors if the system does not exist, or if it is already running.
+    #[inline]
+    pub fn run_system<O: 'static>(&self, id: SystemId<(), O>) -> Result<O, Error> {
+        self.run_system_with_input(id, ())
+    }
+
+    /// Runs a one-shot system with the given input.
+    /// 
+    /// No locks are held while the system runs, so it may use the Ecs (including running other one-shot systems) as it likes. If the system panics, it remains registered and may be run again.
+    /// 
+    /// **Arguments**
+    ///  * `id`: The ID of the system to run.
+    ///  * `input`: The input to give to the system.
+    /// 
+    /// **Returns**  
+    /// The output of the system.
+    /// 
+    /// **Errors**  
+    /// This function errors if the system does not exist, or if it is already running.
+    pub fn run_system_with_input<I: 'static, O: 'static>(&self, id: SystemId<I, O>, input: I) -> Result<O, Error> {
+        // Take the system out of the map while we run it
+        let system: BoxedSystem = match self.systems.lock().1.get_mut(&id.id()) {
+            Some(system) => match system.take() {
+                Some(system) => system,
+                None         => { return Err(Error::SystemAlreadyRunning{ id: id.id() }); },
+            },
+            None => { return Err(Error::UnknownSystem{ id: id.id() }); },
+        };
+
+        // Run it, putting it back afterwards (or when it panics)
+        let mut guard: SystemGuard = SystemGuard{ ecs: self, id: id.id(), system: Some(system) };
+        let output: Box<dyn Any> = match &mut guard.system {
+            Some(system) => system(self, Box::new(input)),
+            None         => { unreachable!(); },
+        };
+        drop(guard);
 
         // Done
-        true
+        Ok(*output.downcast::<O>().expect("Could not downcast system output to its own type"))
     }
 
 
 
-    /// Adds the given component to the given entity.  
-    /// Overwrites any existing component.
+    /// Returns the current change tick of the ECS.
     /// 
-    /// **Generic Types**
-    ///  * `T`: The Component type we want to add.
+    /// Consumers that do not run every tick can store this value when they look at a resource, and later pass it to `Res::is_changed_since()` to learn whether it has changed since (including later in the same tick).
+    #[inline]
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick.load(Ordering::Relaxed)
+    }
+
+    /// Advances the change tick of the ECS, which should be done once per frame.
     /// 
-    /// **Arguments**
-    ///  * `entity`: The Entity to add a component for.
-    ///  * `data`: The data to set the component value to.
+    /// Changes made before this call are no longer reported by `Res::is_changed()`.
     /// 
     /// **Returns**  
-    /// 'true' if the component was added, or 'false' otherwise. It can only fail to be added if the Entity does not exist.
-    pub fn add_component<T: 'static + Component>(&self, entity: Entity, data: T) -> bool {
-        // Get a read lock on the entity list
-        let entities: RwLockReadGuard<(_, HashSet<_>)> = self.entities.read();
+    /// The new change tick.
+    #[inline]
+    pub fn increment_tick(&self) -> u64 {
+        self.change_tick.fetch_add(1, Ordering::Relaxed) + 1
+    }
 
-        // Check if the entity exists
-        if !entities.1.contains(&entity) { return false; }
+    /// Returns the structural generation of the ECS.
+    /// 
+    /// This counter increases whenever entities are spawned or despawned, or components are inserted or removed (but not when they are merely changed). External caches (e.g., a spatial index) can thus compare it with the value they last saw to cheaply find out that nothing structural has changed.
+    /// 
+    /// Note that the counter may also increase for operations that turned out not to change anything.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
 
-        // Try to get the list to insert it into
-        let (_, list) = self.components.get(&ComponentList::<T>::id())
-            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
-        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+    /// Increases the structural generation of the ECS.
+    #[inline]
+    pub(crate) fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::Release);
+    }
 
-        // Perform the insert
-        RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T)).insert(entity, data);
+    /// Starts recording all structural operations (spawns, despawns, inserts and removes) in a Journal.
+    /// 
+    /// This is meant for debugging (e.g., to find out which system keeps despawning the camera), as every structural operation becomes slightly more expensive. Any previously recorded changes are discarded.
+    /// 
+    /// **Arguments**
+    ///  * `frames`: The number of frames (change ticks, see `Ecs::increment_tick()`) to keep changes of.
+    #[inline]
+    pub fn enable_journal(&mut self, frames: u64) {
+        self.journal = Some(Mutex::new(Journal::new(frames)));
+    }
 
-        // Done
-        true
+    /// Stops recording structural operations, discarding the Journal.
+    #[inline]
+    pub fn disable_journal(&mut self) {
+        self.journal = None;
     }
 
-    /// Returns the component of the given Entity.
+    /// Returns the Journal with the structural operations of the last few frames.
     /// 
-    /// The lock returned is actually a lock to the parent ComponentList, so try to keep access to a minimum.
+    /// Note that the Journal is locked while the returned guard lives, so any structural operation on the Ecs blocks until it is dropped.
     /// 
-    /// **Generic Types**
-    ///  * `T`: The Component type we want to get.
+    /// **Returns**  
+    /// The (locked) Journal, or None if it is not enabled (see `Ecs::enable_journal()`).
+    #[inline]
+    pub fn journal(&self) -> Option<MutexGuard<'_, Journal>> {
+        self.journal.as_ref().map(|journal| journal.lock())
+    }
+
+    /// Attributes all structural operations performed by the given closure to the given system in the Journal (if enabled).
+    /// 
+    /// Calls may be nested, in which case the innermost system is used.
+    /// 
+    /// **Arguments**
+    ///  * `system`: The name of the system to attribute the operations to.
+    ///  * `f`: The closure that runs the system.
     /// 
     /// **Returns**  
-    /// An immuteable reference to the Component, or else None if the given entity does not exist or does not have such a Component.
-    pub fn get_component<'a, T: 'static + Component>(&'a self, entity: Entity) -> Option<MappedRwLockReadGuard<'a, T>> {
-        // Get a read lock on the entity list
-        let entities: RwLockReadGuard<(_, HashSet<_>)> = self.entities.read();
+    /// The result of the closure.
+    pub fn attribute<R>(&self, system: &'static str, f: impl FnOnce() -> R) -> R {
+        let previous: Option<&'static str> = match &self.journal {
+            Some(journal) => journal.lock().set_system(Some(system)),
+            None          => { return f(); },
+        };
+        let result: R = f();
+        if let Some(journal) = &self.journal { journal.lock().set_system(previous); }
+        result
+    }
 
-        // Check if the entity exists
-        if !entities.1.contains(&entity) { return None; }
+    /// Records a structural operation in the Journal, if it is enabled.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity that was operated on.
+    ///  * `op`: The operation itself.
+    #[inline]
+    pub(crate) fn record(&self, entity: Entity, op: StructuralOp) {
+        if let Some(journal) = &self.journal {
+            journal.lock().record(self.change_tick(), entity, op);
+        }
+    }
 
-        // Try to get the list to get from
-        let (_, list) = self.components.get(&ComponentList::<T>::id())
-            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
-        let list: RwLockReadGuard<'a, Box<dyn ComponentListBase>> = list.read();
 
-        // Get the casted version of the list
-        let result: MappedRwLockReadGuard<'a, ComponentList<T>> = RwLockReadGuard::map(list, |l| to_component_list!(l, T));
 
-        // Either return None if it doesn't exist, or else the value in a casted guard
-        if result.get(entity).is_none() { return None }
-        Some(MappedRwLockReadGuard::map(result, |r| r.get(entity).unwrap()))
+    /// Inserts a new resource in the ECS, replacing any existing resource of the same type.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The type of the resource.
+    /// 
+    /// **Arguments**
+    ///  * `value`: The value of the resource.
+    /// 
+    /// **Returns**  
+    /// The old value of the resource if there was one, or else None.
+    pub fn insert_resource<T: 'static>(&mut self, value: T) -> Option<T> {
+        let tick: u64 = self.change_tick();
+        self.resources.insert(TypeId::of::<T>(), RwLock::new(ResourceData {
+            value   : Box::new(value),
+            added   : tick,
+            changed : tick,
+        })).map(|old| *old.into_inner().value.downcast::<T>().expect("Could not downcast resource to its own type"))
     }
 
-    /// Returns the component of the given Entity.
+    /// Removes a resource from the ECS.
     /// 
     /// **Generic Types**
-    ///  * `T`: The Component type we want to get.
+    ///  * `T`: The type of the resource.
     /// 
     /// **Returns**  
-    /// A muteable reference to the Component, or else None if the given entity does not exist or does not have such a Component.
-    pub fn get_component_mut<'a, T: 'static + Component>(&'a self, entity: Entity) -> Option<MappedRwLockWriteGuard<'a, T>> {
-        // Get a read lock on the entity list
-        let entities: RwLockReadGuard<(_, HashSet<_>)> = self.entities.read();
+    /// The value of the resource if it existed, or else None.
+    pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
+        self.resources.remove(&TypeId::of::<T>())
+            .map(|old| *old.into_inner().value.downcast::<T>().expect("Could not downcast resource to its own type"))
+    }
 
-        // Check if the entity exists
-        if !entities.1.contains(&entity) { return None; }
+    /// Temporarily removes a resource from the ECS, so that both it and the ECS itself may be borrowed mutably at the same time.
+    /// 
+    /// The resource is reinserted afterwards and marked as changed. If the closure inserted a new resource of the same type in the meantime, it is overwritten by the scoped one.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The type of the resource.
+    ///  * `R`: The type of the value returned by the closure.
+    /// 
+    /// **Arguments**
+    ///  * `f`: The closure to call with the ECS and the resource.
+    /// 
+    /// **Returns**  
+    /// The value returned by the closure, or None if there is no such resource (in which case the closure isn't called).
+    pub fn resource_scope<T: 'static, R>(&mut self, f: impl FnOnce(&mut Ecs, &mut T) -> R) -> Option<R> {
+        let type_id: TypeId = TypeId::of::<T>();
+        let mut data: ResourceData = self.resources.remove(&type_id)?.into_inner();
 
-        // Try to get the list to get from
-        let (_, list) = self.components.get(&ComponentList::<T>::id())
-            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
-        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+        // Call the closure with the resource taken out of the ECS
+        let result: R = f(self, data.value.downcast_mut::<T>().expect("Could not downcast resource to its own type"));
 
-        // Get the casted version of the list
-        let mut result: MappedRwLockWriteGuard<'a, ComponentList<T>> = RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T));
+        // Put it back
+        data.changed = self.change_tick();
+        self.resources.insert(type_id, RwLock::new(data));
+        Some(result)
+    }
 
-        // Either return None if it doesn't exist, or else the value in a casted guard
-        if result.get_mut(entity).is_none() { return None }
-        Some(MappedRwLockWriteGuard::map(result, |r| r.get_mut(entity).unwrap()))
+    /// Returns whether a resource of the given type exists.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The type of the resource.
+    #[inline]
+    pub fn has_resource<T: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<T>())
     }
 
-    /// Returns all entities with the given component type.
+    /// Returns a resource from the ECS.
     /// 
     /// **Generic Types**
-    ///  * `T`: The Component type we want to list.
+    ///  * `T`: The type of the resource.
     /// 
     /// **Returns**  
-    /// An immuteable reference to the list of components.
-    pub fn list_component<T: 'static + Component>(&self) -> MappedRwLockReadGuard<ComponentList<T>> {
-        // Get a read lock on the list in question
-        let (_, list) = self.components.get(&ComponentList::<T>::id())
-            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
-        let list: RwLockReadGuard<Box<dyn ComponentListBase>> = list.read();
-
-        // Return the casted instance of the list
-        RwLockReadGuard::map(list, |l| to_component_list!(l, T))
+    /// An immuteable reference to the resource (which also tells if it has been changed), or None if there is no such resource.
+    pub fn resource<T: 'static>(&self) -> Option<Res<'_, T>> {
+        let data: RwLockReadGuard<ResourceData> = self.resources.get(&TypeId::of::<T>())?.read();
+        Some(Res::new(data, self.change_tick()))
     }
 
-    /// Returns all entities with the given component type.
+    /// Returns a resource from the ECS.
     /// 
     /// **Generic Types**
-    ///  * `T`: The Component type we want to list.
+    ///  * `T`: The type of the resource.
     /// 
     /// **Returns**  
-    /// A muteable reference to the list of components.
-    pub fn list_component_mut<T: 'static + Component>(&self) -> MappedRwLockWriteGuard<ComponentList<T>> {
-        // Get a write lock on the list in question
-        let (_, list) = self.components.get(&ComponentList::<T>::id())
-            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
-        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+    /// A muteable reference to the resource (which marks it as changed when used), or None if there is no such resource.
+    pub fn resource_mut<T: 'static>(&self) -> Option<ResMut<'_, T>> {
+        let data: RwLockWriteGuard<ResourceData> = self.resources.get(&TypeId::of::<T>())?.write();
+        Some(ResMut::new(data, self.change_tick()))
+    }
 
-        // Return the casted instance of the list
-        RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T))
+
+
+    /// Registers a new kind of state machine in the ECS.
+    /// 
+    /// This registers `StateMachine<S>` as a component type, and inserts the `Transitions<S>` and `Events<StateChanged<S>>` resources.
+    /// 
+    /// **Generic Types**
+    ///  * `S`: The type of the state.
+    /// 
+    /// # Arguments
+    /// - `this`: The instance of self to which we registered, wrapped in an Rc.
+    pub fn register_state_machine<S: 'static + Clone + PartialEq>(this: &Rc<RefCell<Self>>) {
+        Self::register::<StateMachine<S>>(this);
+        let mut mthis: RefMut<Self> = this.borrow_mut();
+        mthis.insert_resource(Transitions::<S>::new());
+        mthis.insert_resource(Events::<StateChanged<S>>::new());
     }
 
-    /// Removes a component for the given entity.
+    /// Adds a transition to all state machines of the given kind.
+    /// 
+    /// Transitions are evaluated in the order in which they are added, and at most one is taken per entity per `Ecs::update_state_machines()`.
     /// 
     /// **Generic Types**
-    ///  * `T`: The Component type we want to remove.
+    ///  * `S`: The type of the state. Must have been registered with `Ecs::register_state_machine()`.
     /// 
     /// **Arguments**
-    ///  * `entity`: The Entity to remove the component of.
+    ///  * `from`: The state in which the transition may be taken.
+    ///  * `to`: The state the transition leads to.
+    ///  * `condition`: Decides, for a given entity, whether the transition should be taken. It may read the ECS, but not the `Transitions<S>` resource.
+    /// 
+    /// **Panics**  
+    /// This function panics if the state machine kind has not been registered.
+    pub fn add_transition<S: 'static + Clone + PartialEq>(&self, from: S, to: S, condition: impl 'static + Fn(&Ecs, Entity) -> bool) {
+        self.resource_mut::<Transitions<S>>()
+            .unwrap_or_else(|| panic!("Unregistered state machine '{}'", std::any::type_name::<S>()))
+            .add(from, to, condition);
+    }
+
+    /// Evaluates the transitions of all state machines of the given kind, and sends a `StateChanged<S>` event for every state machine that changed.
+    /// 
+    /// The conditions are evaluated without holding a lock on the state machines, so they may read them.
+    /// 
+    /// **Generic Types**
+    ///  * `S`: The type of the state. Must have been registered with `Ecs::register_state_machine()`.
     /// 
     /// **Returns**  
-    /// Returns the removed component if it existed, or else None.
-    #[inline]
-    pub fn remove_component<T: 'static + Component>(&mut self, entity: Entity) -> Option<T> {
-        // Get a write lock on the list in question
-        let (_, list) = self.components.get_mut(&ComponentList::<T>::id())
-            .expect(&format!("Unregistered Component type '{:?}'", ComponentList::<T>::id()));
-        let list: RwLockWriteGuard<Box<dyn ComponentListBase>> = list.write();
+    /// The number of state machines that changed state.
+    /// 
+    /// **Panics**  
+    /// This function panics if the state machine kind has not been registered.
+    pub fn update_state_machines<S: 'static + Clone + PartialEq>(&self) -> usize {
+        // Snapshot the current states
+        let states: Vec<(Entity, S)> = self.list_component::<StateMachine<S>>().iter_entities().map(|(e, m)| (e, m.state().clone())).collect();
 
-        // Remove it
-        RwLockWriteGuard::map(list, |l| to_component_list_mut!(l, T)).remove(entity)
+        // Find the transitions to take
+        let changes: Vec<StateChanged<S>> = {
+            let transitions: Res<Transitions<S>> = self.resource::<Transitions<S>>()
+                .unwrap_or_else(|| panic!("Unregistered state machine '{}'", std::any::type_name::<S>()));
+            states.into_iter().filter_map(|(entity, from)| {
+                let to: S = transitions.next(self, entity, &from)?.clone();
+                Some(StateChanged{ entity, from, to })
+            }).collect()
+        };
+
+        // Apply them, and report them
+        let mut list: MappedRwLockWriteGuard<ComponentList<StateMachine<S>>> = self.list_component_mut::<StateMachine<S>>();
+        let mut events: ResMut<Events<StateChanged<S>>> = self.resource_mut::<Events<StateChanged<S>>>()
+            .unwrap_or_else(|| panic!("Unregistered state machine '{}'", std::any::type_name::<S>()));
+        let mut n_changes: usize = 0;
+        for change in changes {
+            // The machine may have been removed by a condition in the meantime
+            if let Some(machine) = list.get_mut(change.entity) {
+                *machine = StateMachine::new(change.to.clone());
+                events.send(change);
+                n_changes += 1;
+            }
+        }
+        n_changes
     }
 }
@@ -0,0 +1,326 @@
+//  TESTING.rs
+//    by Lut99
+// 
+//  Created:
+//    15 Oct 2026, 23:55:11
+//  Last edited:
+//    16 Oct 2026, 00:51:47
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Provides helpers for testing code that uses the Ecs, by comparing
+//!   the state of a world against an expected description. Only
+//!   available with the `testing` feature.
+// 
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
+
+use crate::spec::{Component, Entity};
+use crate::system::Ecs;
+
+
+/***** UNIT TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Simple component to test with
+    #[derive(Debug)]
+    struct Health(u32);
+    impl Component for Health {}
+
+    /// Another simple component to test with
+    #[derive(Debug)]
+    struct Player;
+    impl Component for Player {}
+
+    /// Tests capturing and comparing world states
+    #[test]
+    fn test_world_state() {
+        let ecs = Ecs::new(16);
+        Ecs::register::<Health>(&ecs);
+        Ecs::register::<Player>(&ecs);
+        let ecs = ecs.borrow();
+
+        let a: Entity = ecs.add_entity();
+        let b: Entity = ecs.add_entity();
+        ecs.add_component(a, Health(10));
+        ecs.add_component(a, Player);
+        ecs.add_component(b, Health(3));
+
+        // Let health decay for a few ticks
+        run_ticks(&ecs, 2, |ecs| {
+            for health in ecs.list_component_mut::<Health>().iter_mut() {
+                health.0 = health.0.saturating_sub(2);
+            }
+        });
+
+        // Compare it against what we expect
+        let inspector = Inspector::new().with::<Health>().with::<Player>();
+        let expected = WorldState::new()
+            .component(a, Health(6)).component(a, Player)
+            .component(b, Health(0));
+        inspector.capture(&ecs).assert_eq(&expected);
+
+        // Differences are reported per component
+        let wrong = WorldState::new()
+            .component(a, Health(6))
+            .component(b, Health(1));
+        let diff: Vec<String> = inspector.capture(&ecs).diff(&wrong);
+        assert_eq!(diff, vec![
+            format!("entity {}: unexpected Player: Player", a),
+            format!("entity {}: Health is Health(0), expected Health(1)", b),
+        ]);
+    }
+
+    /// Tests that generic component types are named (and thus compared) in full
+    #[test]
+    fn test_short_name() {
+        #[derive(Debug)]
+        struct Wrapper<T>(T);
+        impl<T: 'static> Component for Wrapper<T> {}
+
+        assert_eq!(short_name::<Health>(), "Health");
+        assert_eq!(short_name::<crate::relation::Relation<crate::hierarchy::ChildOf>>(), "Relation<ChildOf>");
+        assert_eq!(short_name::<(Health, Option<&[Wrapper<Player>; 2]>)>(), "(Health, Option<&[Wrapper<Player>; 2]>)");
+
+        // Wrappers of different types do not collapse into one
+        let ecs = Ecs::new(16);
+        Ecs::register::<Wrapper<Health>>(&ecs);
+        Ecs::register::<Wrapper<Player>>(&ecs);
+        let ecs = ecs.borrow();
+        let a: Entity = ecs.add_entity();
+        ecs.add_component(a, Wrapper(Health(1)));
+        ecs.add_component(a, Wrapper(Player));
+        let expected = WorldState::new().component(a, Wrapper(Health(1))).component(a, Wrapper(Player));
+        Inspector::new().with::<Wrapper<Health>>().with::<Wrapper<Player>>().capture(&ecs).assert_eq(&expected);
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A function that adds the components of a single type to a WorldState.
+type Describer = Box<dyn Fn(&Ecs, &mut WorldState)>;
+
+
+
+/// Returns the name of a type without its path, including the paths of any generic arguments (e.g., `Relation<ChildOf>` instead of `rust_ecs::relation::Relation<rust_ecs::hierarchy::ChildOf>`).
+/// 
+/// **Generic Types**
+///  * `T`: The type to return the name of.
+fn short_name<T>() -> String {
+    let name: &'static str = std::any::type_name::<T>();
+    let mut result: String = String::with_capacity(name.len());
+    let mut start: usize = 0;
+    for (i, c) in name.char_indices().filter(|(_, c)| matches!(c, '<' | '>' | ',' | '(' | ')' | '[' | ']' | ';' | '&' | ' ')) {
+        let segment: &str = &name[start..i];
+        result.push_str(segment.rsplit("::").next().unwrap_or(segment));
+        result.push(c);
+        start = i + c.len_utf8();
+    }
+    let segment: &str = &name[start..];
+    result.push_str(segment.rsplit("::").next().unwrap_or(segment));
+    result
+}
+
+
+
+
+
+/***** LIBRARY FUNCTIONS *****/
+/// Runs the given closure for the given number of ticks, advancing the change tick of the Ecs after each one.
+/// 
+/// **Arguments**
+///  * `ecs`: The Ecs to run the ticks on.
+///  * `ticks`: The number of ticks to run.
+///  * `tick`: The closure that implements a single tick (e.g., by running the systems under test in order).
+pub fn run_ticks(ecs: &Ecs, ticks: usize, mut tick: impl FnMut(&Ecs)) {
+    for _ in 0..ticks {
+        tick(ecs);
+        ecs.increment_tick();
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Captures the state of an Ecs as a WorldState.
+/// 
+/// Because components are stored type-erased, every component type that should be captured has to be added explicitly.
+#[derive(Default)]
+pub struct Inspector {
+    /// The functions that capture a single component type each.
+    describers : Vec<Describer>,
+}
+
+impl Inspector {
+    /// Constructor for the Inspector, which initially captures only which entities exist.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            describers : vec![],
+        }
+    }
+
+
+
+    /// Captures the given component type as well.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to capture. Must be registered in the Ecs captured later.
+    pub fn with<T: 'static + Component + Debug>(mut self) -> Self {
+        self.describers.push(Box::new(|ecs: &Ecs, state: &mut WorldState| {
+            for (entity, component) in ecs.list_component::<T>().iter_entities() {
+                state.insert(entity, short_name::<T>(), format!("{:?}", component));
+            }
+        }));
+        self
+    }
+
+
+
+    /// Captures the current state of the given Ecs.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to capture.
+    /// 
+    /// **Returns**  
+    /// A WorldState that describes all entities and the captured components.
+    pub fn capture(&self, ecs: &Ecs) -> WorldState {
+        let mut state: WorldState = WorldState::new();
+        for entity in ecs.entities() {
+            state.entities.entry(entity).or_default();
+        }
+        for describe in &self.describers {
+            describe(ecs, &mut state);
+        }
+        state
+    }
+}
+
+
+
+/// Describes the entities in an Ecs and (some of) their components, using their Debug representation.
+/// 
+/// It is either captured from an Ecs with an Inspector, or constructed by hand to describe the expected state.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WorldState {
+    /// Maps every entity to its components, which map their type name to their Debug representation.
+    entities : BTreeMap<Entity, BTreeMap<String, String>>,
+}
+
+impl WorldState {
+    /// Constructor for the WorldState, which initially describes an empty world.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            entities : BTreeMap::new(),
+        }
+    }
+
+
+
+    /// Adds an entity without (captured) components to the description.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity to add.
+    #[inline]
+    pub fn entity(mut self, entity: Entity) -> Self {
+        self.entities.entry(entity).or_default();
+        self
+    }
+
+    /// Adds a component to the description, adding its entity if needed.
+    /// 
+    /// **Generic Types**
+    ///  * `T`: The Component type to add.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity that has the component.
+    ///  * `component`: The value of the component.
+    #[inline]
+    pub fn component<T: Component + Debug>(mut self, entity: Entity, component: T) -> Self {
+        self.insert(entity, short_name::<T>(), format!("{:?}", component));
+        self
+    }
+
+    /// Inserts a described component.
+    /// 
+    /// **Arguments**
+    ///  * `entity`: The Entity that has the component.
+    ///  * `name`: The name of the Component type.
+    ///  * `value`: The Debug representation of the component.
+    #[inline]
+    fn insert(&mut self, entity: Entity, name: String, value: String) {
+        self.entities.entry(entity).or_default().insert(name, value);
+    }
+
+
+
+    /// Compares this (actual) state with the expected one.
+    /// 
+    /// **Arguments**
+    ///  * `expected`: The WorldState we expected.
+    /// 
+    /// **Returns**  
+    /// One human-readable line per difference, or an empty list if the states are equal.
+    pub fn diff(&self, expected: &WorldState) -> Vec<String> {
+        let mut result: Vec<String> = vec![];
+        let empty: BTreeMap<String, String> = BTreeMap::new();
+        let all: BTreeSet<Entity> = self.entities.keys().chain(expected.entities.keys()).copied().collect();
+        for entity in all {
+            let (actual, wanted) = match (self.entities.get(&entity), expected.entities.get(&entity)) {
+                (Some(_), None) => { result.push(format!("entity {}: exists, but was not expected", entity)); continue; },
+                (None, Some(_)) => { result.push(format!("entity {}: does not exist, but was expected", entity)); continue; },
+                (actual, wanted) => (actual.unwrap_or(&empty), wanted.unwrap_or(&empty)),
+            };
+
+            // Compare the components
+            let names: BTreeSet<&String> = actual.keys().chain(wanted.keys()).collect();
+            for name in names {
+                match (actual.get(name), wanted.get(name)) {
+                    (Some(a), None)              => result.push(format!("entity {}: unexpected {}: {}", entity, name, a)),
+                    (None, Some(w))              => result.push(format!("entity {}: missing {}, expected {}", entity, name, w)),
+                    (Some(a), Some(w)) if a != w => result.push(format!("entity {}: {} is {}, expected {}", entity, name, a, w)),
+                    _                            => {},
+                }
+            }
+        }
+        result
+    }
+
+    /// Asserts that this (actual) state equals the expected one.
+    /// 
+    /// **Arguments**
+    ///  * `expected`: The WorldState we expected.
+    /// 
+    /// **Panics**  
+    /// This function panics if the states differ, listing all differences.
+    #[track_caller]
+    pub fn assert_eq(&self, expected: &WorldState) {
+        let diff: Vec<String> = self.diff(expected);
+        if !diff.is_empty() {
+            panic!("World state differs from the expected state:\n  {}", diff.join("\n  "));
+        }
+    }
+}
+
+impl Display for WorldState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        for (entity, components) in &self.entities {
+            write!(f, "{}:", entity)?;
+            for value in components.values() {
+                write!(f, " {}", value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+//  VIEW.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:10:36
+//  Last edited:
+//    16 Oct 2026, 00:10:36
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines the WorldView, a read-only handle to the Ecs that only
+//!   exposes its non-mutating functions.
+// 
+
+use std::any::TypeId;
+
+use parking_lot::MappedRwLockReadGuard;
+
+pub use crate::errors::EcsError as Error;
+use crate::spec::{Component, Entity, WeakEntity};
+use crate::list::ComponentList;
+use crate::resources::Res;
+use crate::snapshot::{QuerySnapshot, Snapshot};
+use crate::schema::ComponentSchema;
+use crate::system::Ecs;
+
+
+/***** LIBRARY *****/
+/// A read-only handle to the Ecs, as returned by `Ecs::view()`.
+/// 
+/// It only exposes functions that do not change the Ecs (they lock lists for reading only), so code that takes a WorldView can be trusted not to mutate the world by its signature alone. All functions behave like their counterparts on the Ecs.
+#[derive(Clone, Copy)]
+pub struct WorldView<'a> {
+    /// The Ecs we provide a view on.
+    ecs : &'a Ecs,
+}
+
+impl<'a> WorldView<'a> {
+    /// Constructor for the WorldView.
+    /// 
+    /// **Arguments**
+    ///  * `ecs`: The Ecs to provide a view on.
+    #[inline]
+    pub(crate) fn new(ecs: &'a Ecs) -> Self {
+        Self {
+            ecs,
+        }
+    }
+
+
+
+    /// Returns whether the given component type is registered. See `Ecs::is_registered()`.
+    #[inline]
+    pub fn is_registered<T: 'static + Component>(&self) -> bool { self.ecs.is_registered::<T>() }
+
+    /// Returns the TypeId of a registered component type by its name. See `Ecs::component_type_id()`.
+    #[inline]
+    pub fn component_type_id(&self, name: &str) -> Option<TypeId> { self.ecs.component_type_id(name) }
+
+    /// Returns the layout of all registered component types. See `Ecs::schema()`.
+    #[inline]
+    pub fn schema(&self) -> ComponentSchema { self.ecs.schema() }
+
+
+
+    /// Returns a snapshot of the existing entities. See `Ecs::entities()`.
+    #[inline]
+    pub fn entities(&self) -> Vec<Entity> { self.ecs.entities() }
+
+    /// Returns whether the given entity exists. See `Ecs::is_alive()`.
+    #[inline]
+    pub fn is_alive(&self, entity: Entity) -> bool { self.ecs.is_alive(entity) }
+
+    /// Resolves a WeakEntity to its entity if it still exists. See `Ecs::resolve()`.
+    #[inline]
+    pub fn resolve(&self, weak: WeakEntity) -> Option<Entity> { self.ecs.resolve(weak) }
+
+
+
+    /// Returns the component of the given entity. See `Ecs::get_component()`.
+    #[inline]
+    pub fn get_component<T: 'static + Component>(&self, entity: Entity) -> Option<MappedRwLockReadGuard<'a, T>> { self.ecs.get_component::<T>(entity) }
+
+    /// Returns the component of the given entity without blocking. See `Ecs::try_get_component()`.
+    #[inline]
+    pub fn try_get_component<T: 'static + Component>(&self, entity: Entity) -> Result<Option<MappedRwLockReadGuard<'a, T>>, Error> { self.ecs.try_get_component::<T>(entity) }
+
+    /// Returns the list of the given component type. See `Ecs::list_component()`.
+    #[inline]
+    pub fn list_component<T: 'static + Component>(&self) -> MappedRwLockReadGuard<'a, ComponentList<T>> { self.ecs.list_component::<T>() }
+
+    /// Returns the list of the given component type without blocking. See `Ecs::try_list_component()`.
+    #[inline]
+    pub fn try_list_component<T: 'static + Component>(&self) -> Result<MappedRwLockReadGuard<'a, ComponentList<T>>, Error> { self.ecs.try_list_component::<T>() }
+
+    /// Calls the given closure for every component of the given type. See `Ecs::for_each()`.
+    #[inline]
+    pub fn for_each<T: 'static + Component>(&self, f: impl FnMut(Entity, &T)) { self.ecs.for_each::<T>(f) }
+
+    /// Captures an owned copy of the given components. See `Ecs::snapshot()`.
+    #[inline]
+    pub fn snapshot<Q: Snapshot>(&self) -> QuerySnapshot<Q> { self.ecs.snapshot::<Q>() }
+
+    /// Returns the value the component of the given entity had when it was last stored. See `Ecs::get_previous()`.
+    #[inline]
+    pub fn get_previous<T: 'static + Component>(&self, entity: Entity) -> Option<MappedRwLockReadGuard<'a, T>> { self.ecs.get_previous::<T>(entity) }
+
+
+
+    /// Returns the target of the given relation of an entity. See `Ecs::related()`.
+    #[inline]
+    pub fn related<R: 'static>(&self, source: Entity) -> Option<Entity> { self.ecs.related::<R>(source) }
+
+    /// Returns all entities with the given relation to the target. See `Ecs::targeting()`.
+    #[inline]
+    pub fn targeting<R: 'static>(&self, target: Entity) -> Vec<Entity> { self.ecs.targeting::<R>(target) }
+
+    /// Returns the parent of the given entity. See `Ecs::parent()`.
+    #[inline]
+    pub fn parent(&self, child: Entity) -> Option<Entity> { self.ecs.parent(child) }
+
+    /// Returns the children of the given entity. See `Ecs::children()`.
+    #[inline]
+    pub fn children(&self, parent: Entity) -> Vec<Entity> { self.ecs.children(parent) }
+
+
+
+    /// Returns the current change tick. See `Ecs::change_tick()`.
+    #[inline]
+    pub fn change_tick(&self) -> u64 { self.ecs.change_tick() }
+
+    /// Returns the structural generation counter. See `Ecs::generation()`.
+    #[inline]
+    pub fn generation(&self) -> u64 { self.ecs.generation() }
+
+    /// Returns whether a resource of the given type exists. See `Ecs::has_resource()`.
+    #[inline]
+    pub fn has_resource<T: 'static>(&self) -> bool { self.ecs.has_resource::<T>() }
+
+    /// Returns the resource of the given type. See `Ecs::resource()`.
+    #[inline]
+    pub fn resource<T: 'static>(&self) -> Option<Res<'a, T>> { self.ecs.resource::<T>() }
+}
@@ -0,0 +1,515 @@
+//  WIRE.rs
+//    by Lut99
+// 
+//  Created:
+//    16 Oct 2026, 00:04:17
+//  Last edited:
+//    16 Oct 2026, 00:32:44
+//  Auto updated?
+//    Yes
+// 
+//  Description:
+//!   Defines a compact, versioned binary encoding for component values,
+//!   which may be shared between saves and network replication.
+// 
+
+use parking_lot::MappedRwLockReadGuard;
+
+pub use crate::errors::EcsError as Error;
+use crate::spec::{Component, Entity, Validate};
+use crate::list::ComponentList;
+use crate::system::Ecs;
+
+
+/***** UNIT TESTS *****/
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Tests that values survive a round trip
+    #[test]
+    fn test_roundtrip() {
+        let mut writer: WireWriter = WireWriter::new();
+        42u8.encode(&mut writer);
+        300u64.encode(&mut writer);
+        (-2i32).encode(&mut writer);
+        1.5f32.encode(&mut writer);
+        true.encode(&mut writer);
+        String::from("héllo").encode(&mut writer);
+        Entity::try_from(7u64).unwrap().encode(&mut writer);
+        vec![ Some(1u16), None ].encode(&mut writer);
+        let bytes: Vec<u8> = writer.into_bytes();
+
+        let mut reader: WireReader = WireReader::new(&bytes);
+        assert_eq!(u8::decode(&mut reader).unwrap(), 42);
+        assert_eq!(u64::decode(&mut reader).unwrap(), 300);
+        assert_eq!(i32::decode(&mut reader).unwrap(), -2);
+        assert_eq!(f32::decode(&mut reader).unwrap(), 1.5);
+        assert!(bool::decode(&mut reader).unwrap());
+        assert_eq!(String::decode(&mut reader).unwrap(), "héllo");
+        assert_eq!(u64::from(Entity::decode(&mut reader).unwrap()), 7);
+        assert_eq!(Vec::<Option<u16>>::decode(&mut reader).unwrap(), vec![ Some(1), None ]);
+        assert!(reader.is_empty());
+
+        // Varints are compact
+        let mut writer: WireWriter = WireWriter::new();
+        writer.write_varint(127);
+        writer.write_varint(128);
+        assert_eq!(writer.into_bytes(), vec![ 0x7F, 0x80, 0x01 ]);
+    }
+
+    /// Tests that invalid input is rejected instead of panicking
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(u32::decode(&mut WireReader::new(&[ 0x80 ])), Err(Error::WireUnexpectedEnd)));
+        assert!(matches!(u64::decode(&mut WireReader::new(&[ 0xFF; 11 ])), Err(Error::WireVarintOverflow)));
+        assert!(matches!(u8::decode(&mut WireReader::new(&[ 0x80, 0x02 ])), Err(Error::WireInvalidValue{ .. })));
+        assert!(matches!(bool::decode(&mut WireReader::new(&[ 2 ])), Err(Error::WireInvalidValue{ .. })));
+        assert!(matches!(Entity::decode(&mut WireReader::new(&[ 0 ])), Err(Error::NullEntity)));
+        assert!(matches!(Vec::<u8>::decode(&mut WireReader::new(&[ 0xFF, 0xFF, 0xFF, 0xFF, 0x0F ])), Err(Error::WireUnexpectedEnd)));
+        assert!(matches!(String::decode(&mut WireReader::new(&[ 1, 0xFF ])), Err(Error::WireInvalidValue{ .. })));
+        assert!(matches!(decode_components::<Score>(&[ WIRE_VERSION + 1, 0 ]), Err(Error::WireUnsupportedVersion{ .. })));
+    }
+
+    /// Simple component to test with
+    #[derive(Debug, PartialEq)]
+    struct Score(u32);
+    impl Component for Score {}
+    impl WireCodec for Score {
+        fn encode(&self, writer: &mut WireWriter) { self.0.encode(writer); }
+        fn decode(reader: &mut WireReader) -> Result<Self, Error> { Ok(Self(u32::decode(reader)?)) }
+    }
+    impl Validate for Score {
+        fn validate(&self) -> Result<(), String> {
+            if self.0 > 100 { Err("score exceeds 100".into()) } else { Ok(()) }
+        }
+    }
+
+    /// Tests encoding and decoding entire component lists
+    #[test]
+    fn test_components() {
+        let (ecs, [ a, b ]) = crate::world!(Score;
+            { Score(10) },
+            { Score(20) },
+        );
+        let bytes: Vec<u8> = encode_components::<Score>(&ecs.borrow());
+        assert_eq!(decode_components::<Score>(&bytes).unwrap(), vec![ (a, Score(10)), (b, Score(20)) ]);
+        assert_eq!(decode_components_validated::<Score>(&bytes).unwrap().len(), 2);
+
+        // Invalid components are refused when validating
+        ecs.borrow().add_component(b, Score(200));
+        let bytes: Vec<u8> = encode_components::<Score>(&ecs.borrow());
+        assert_eq!(decode_components::<Score>(&bytes).unwrap()[1], (b, Score(200)));
+        assert!(matches!(decode_components_validated::<Score>(&bytes), Err(Error::InvalidComponent{ entity, .. }) if entity == b));
+    }
+
+    proptest! {
+        /// Tests that decoding arbitrary bytes never panics (see also the `wire_decode` fuzz target)
+        #[test]
+        fn test_arbitrary_bytes(bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+            let _ = decode_components::<Score>(&bytes);
+            let _ = decode_components_validated::<Score>(&bytes);
+            let _ = u64::decode(&mut WireReader::new(&bytes));
+            let _ = i32::decode(&mut WireReader::new(&bytes));
+            let _ = f64::decode(&mut WireReader::new(&bytes));
+            let _ = String::decode(&mut WireReader::new(&bytes));
+            let _ = Entity::decode(&mut WireReader::new(&bytes));
+            let _ = Vec::<Option<bool>>::decode(&mut WireReader::new(&bytes));
+        }
+
+        /// Tests that every component list survives a round trip
+        #[test]
+        fn test_components_roundtrip(values in proptest::collection::vec(any::<u32>(), 0..16)) {
+            let ecs = crate::world!(Score);
+            let ecs = ecs.borrow();
+            let expected: Vec<(Entity, Score)> = values.iter().map(|v| { let e = ecs.add_entity(); ecs.add_component(e, Score(*v)); (e, Score(*v)) }).collect();
+            prop_assert_eq!(decode_components::<Score>(&encode_components::<Score>(&ecs)).unwrap(), expected);
+        }
+    }
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The version of the wire format, which is written at the start of every encoded component list.
+pub const WIRE_VERSION: u8 = 1;
+
+
+
+
+
+/***** LIBRARY FUNCTIONS *****/
+/// Encodes all components of the given type.
+/// 
+/// The result starts with the `WIRE_VERSION` and the number of components, followed by every (entity, component) pair in order of entity.
+/// 
+/// **Generic Types**
+///  * `T`: The Component type to encode.
+/// 
+/// **Arguments**
+///  * `ecs`: The Ecs to encode the components of.
+/// 
+/// **Returns**  
+/// The encoded components.
+pub fn encode_components<T: 'static + Component + WireCodec>(ecs: &Ecs) -> Vec<u8> {
+    let list: MappedRwLockReadGuard<ComponentList<T>> = ecs.list_component::<T>();
+    let mut components: Vec<(Entity, &T)> = list.iter_entities().collect();
+    components.sort_by_key(|(entity, _)| *entity);
+
+    // Write the header, then the components
+    let mut writer: WireWriter = WireWriter::new();
+    writer.write_u8(WIRE_VERSION);
+    writer.write_varint(components.len() as u64);
+    for (entity, component) in components {
+        entity.encode(&mut writer);
+        component.encode(&mut writer);
+    }
+    writer.into_bytes()
+}
+
+/// Decodes a list of components encoded by `encode_components()`.
+/// 
+/// The components are not added to any Ecs, as the caller may have to map the entities first.
+/// 
+/// **Generic Types**
+///  * `T`: The Component type to decode.
+/// 
+/// **Arguments**
+///  * `bytes`: The encoded components.
+/// 
+/// **Returns**  
+/// The decoded (entity, component) pairs, in order of entity.
+/// 
+/// **Errors**  
+/// This function errors if the data has an unsupported version, or is otherwise invalid.
+pub fn decode_components<T: WireCodec>(bytes: &[u8]) -> Result<Vec<(Entity, T)>, Error> {
+    let mut reader: WireReader = WireReader::new(bytes);
+    let version: u8 = reader.read_u8()?;
+    if version != WIRE_VERSION { return Err(Error::WireUnsupportedVersion{ got: version, expected: WIRE_VERSION }); }
+
+    // Read the components
+    let len: usize = reader.read_len()?;
+    let mut result: Vec<(Entity, T)> = Vec::with_capacity(len.min(reader.remaining()));
+    for _ in 0..len {
+        result.push((Entity::decode(&mut reader)?, T::decode(&mut reader)?));
+    }
+    if !reader.is_empty() { return Err(Error::WireInvalidValue{ what: "trailing data" }); }
+    Ok(result)
+}
+
+/// Decodes a list of components encoded by `encode_components()`, and validates every one of them (see `Validate`).
+/// 
+/// Use this for data from untrusted sources (e.g., save files or the network), so invalid values never make it into the Ecs.
+/// 
+/// **Generic Types**
+///  * `T`: The Component type to decode.
+/// 
+/// **Arguments**
+///  * `bytes`: The encoded components.
+/// 
+/// **Returns**  
+/// The decoded (entity, component) pairs, in order of entity.
+/// 
+/// **Errors**  
+/// This function errors if the data is invalid (see `decode_components()`), or if any of the components fails validation.
+pub fn decode_components_validated<T: WireCodec + Validate>(bytes: &[u8]) -> Result<Vec<(Entity, T)>, Error> {
+    let result: Vec<(Entity, T)> = decode_components(bytes)?;
+    for (entity, component) in &result {
+        if let Err(reason) = component.validate() { return Err(Error::InvalidComponent{ name: std::any::type_name::<T>(), entity: *entity, reason }); }
+    }
+    Ok(result)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Writes values in the wire format.
+#[derive(Clone, Debug, Default)]
+pub struct WireWriter {
+    /// The bytes written so far.
+    bytes : Vec<u8>,
+}
+
+impl WireWriter {
+    /// Constructor for the WireWriter.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            bytes : vec![],
+        }
+    }
+
+
+
+    /// Writes a single byte.
+    /// 
+    /// **Arguments**
+    ///  * `value`: The byte to write.
+    #[inline]
+    pub fn write_u8(&mut self, value: u8) { self.bytes.push(value); }
+
+    /// Writes an unsigned integer as a LEB128 varint (i.e., 7 bits per byte, with the high bit set on all but the last byte).
+    /// 
+    /// **Arguments**
+    ///  * `value`: The integer to write.
+    pub fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    /// Writes raw bytes, without a length prefix.
+    /// 
+    /// **Arguments**
+    ///  * `bytes`: The bytes to write.
+    #[inline]
+    pub fn write_raw(&mut self, bytes: &[u8]) { self.bytes.extend_from_slice(bytes); }
+
+
+
+    /// Returns the bytes written so far.
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> { self.bytes }
+}
+
+
+
+/// Reads values in the wire format.
+/// 
+/// Reading never panics or allocates more than the input could possibly contain, so it is safe to use on untrusted input.
+#[derive(Clone, Debug)]
+pub struct WireReader<'a> {
+    /// The bytes that have not been read yet.
+    bytes : &'a [u8],
+}
+
+impl<'a> WireReader<'a> {
+    /// Constructor for the WireReader.
+    /// 
+    /// **Arguments**
+    ///  * `bytes`: The bytes to read from.
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+        }
+    }
+
+
+
+    /// Reads a single byte.
+    /// 
+    /// **Errors**  
+    /// This function errors if there are no bytes left.
+    #[inline]
+    pub fn read_u8(&mut self) -> Result<u8, Error> {
+        let (first, rest) = self.bytes.split_first().ok_or(Error::WireUnexpectedEnd)?;
+        self.bytes = rest;
+        Ok(*first)
+    }
+
+    /// Reads an unsigned integer written as a LEB128 varint.
+    /// 
+    /// **Errors**  
+    /// This function errors if the input ends before the varint does, or if it does not fit in 64 bits.
+    pub fn read_varint(&mut self) -> Result<u64, Error> {
+        let mut result: u64 = 0;
+        let mut shift: u32 = 0;
+        loop {
+            let byte: u8 = self.read_u8()?;
+            if shift == 63 && byte > 1 { return Err(Error::WireVarintOverflow); }
+            result |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 { return Ok(result); }
+            shift += 7;
+            if shift > 63 { return Err(Error::WireVarintOverflow); }
+        }
+    }
+
+    /// Reads a length (e.g., of a string or a vector) written as a varint.
+    /// 
+    /// **Errors**  
+    /// This function errors if the length is not a valid varint, or if it is larger than the remaining input (as every element takes at least one byte).
+    pub fn read_len(&mut self) -> Result<usize, Error> {
+        let len: u64 = self.read_varint()?;
+        if len > self.bytes.len() as u64 { return Err(Error::WireUnexpectedEnd); }
+        Ok(len as usize)
+    }
+
+    /// Reads the given number of raw bytes.
+    /// 
+    /// **Arguments**
+    ///  * `len`: The number of bytes to read.
+    /// 
+    /// **Errors**  
+    /// This function errors if there are not enough bytes left.
+    pub fn read_raw(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.bytes.len() { return Err(Error::WireUnexpectedEnd); }
+        let (result, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(result)
+    }
+
+
+
+    /// Returns the number of bytes that have not been read yet.
+    #[inline]
+    pub fn remaining(&self) -> usize { self.bytes.len() }
+
+    /// Returns whether all bytes have been read.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.bytes.is_empty() }
+}
+
+
+
+/// Defines how a type is encoded in the wire format.
+/// 
+/// Implement this for every component type that should be saved or replicated.
+pub trait WireCodec: Sized {
+    /// Encodes this value.
+    /// 
+    /// **Arguments**
+    ///  * `writer`: The WireWriter to write the value to.
+    fn encode(&self, writer: &mut WireWriter);
+
+    /// Decodes a value.
+    /// 
+    /// **Arguments**
+    ///  * `reader`: The WireReader to read the value from.
+    /// 
+    /// **Errors**  
+    /// This function errors if the input is not a valid encoding of this type.
+    fn decode(reader: &mut WireReader) -> Result<Self, Error>;
+}
+
+/// Implements WireCodec for unsigned integers, which are encoded as varints.
+macro_rules! impl_codec_unsigned {
+    ($($t:ty),+) => {
+        $(impl WireCodec for $t {
+            #[inline]
+            fn encode(&self, writer: &mut WireWriter) { writer.write_varint(*self as u64); }
+            #[inline]
+            fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+                <$t>::try_from(reader.read_varint()?).map_err(|_| Error::WireInvalidValue{ what: stringify!($t) })
+            }
+        })+
+    };
+}
+impl_codec_unsigned!(u8, u16, u32, u64, usize);
+
+/// Implements WireCodec for signed integers, which are zigzag-encoded as varints (so small negative numbers stay small).
+macro_rules! impl_codec_signed {
+    ($($t:ty),+) => {
+        $(impl WireCodec for $t {
+            #[inline]
+            fn encode(&self, writer: &mut WireWriter) {
+                let value: i64 = *self as i64;
+                writer.write_varint(((value << 1) ^ (value >> 63)) as u64);
+            }
+            #[inline]
+            fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+                let raw: u64 = reader.read_varint()?;
+                let value: i64 = ((raw >> 1) as i64) ^ -((raw & 1) as i64);
+                <$t>::try_from(value).map_err(|_| Error::WireInvalidValue{ what: stringify!($t) })
+            }
+        })+
+    };
+}
+impl_codec_signed!(i8, i16, i32, i64, isize);
+
+impl WireCodec for f32 {
+    #[inline]
+    fn encode(&self, writer: &mut WireWriter) { writer.write_raw(&self.to_le_bytes()); }
+    #[inline]
+    fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+        Ok(f32::from_le_bytes(reader.read_raw(4)?.try_into().unwrap()))
+    }
+}
+
+impl WireCodec for f64 {
+    #[inline]
+    fn encode(&self, writer: &mut WireWriter) { writer.write_raw(&self.to_le_bytes()); }
+    #[inline]
+    fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+        Ok(f64::from_le_bytes(reader.read_raw(8)?.try_into().unwrap()))
+    }
+}
+
+impl WireCodec for bool {
+    #[inline]
+    fn encode(&self, writer: &mut WireWriter) { writer.write_u8(*self as u8); }
+    #[inline]
+    fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+        match reader.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::WireInvalidValue{ what: "bool" }),
+        }
+    }
+}
+
+impl WireCodec for String {
+    #[inline]
+    fn encode(&self, writer: &mut WireWriter) {
+        writer.write_varint(self.len() as u64);
+        writer.write_raw(self.as_bytes());
+    }
+    #[inline]
+    fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+        let len: usize = reader.read_len()?;
+        let bytes: &[u8] = reader.read_raw(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error::WireInvalidValue{ what: "string" })
+    }
+}
+
+impl WireCodec for Entity {
+    #[inline]
+    fn encode(&self, writer: &mut WireWriter) { writer.write_varint(u64::from(*self)); }
+    #[inline]
+    fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+        Entity::try_from(reader.read_varint()?)
+    }
+}
+
+impl<T: WireCodec> WireCodec for Option<T> {
+    #[inline]
+    fn encode(&self, writer: &mut WireWriter) {
+        match self {
+            Some(value) => { writer.write_u8(1); value.encode(writer); },
+            None        => { writer.write_u8(0); },
+        }
+    }
+    #[inline]
+    fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+        match reader.read_u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(reader)?)),
+            _ => Err(Error::WireInvalidValue{ what: "option tag" }),
+        }
+    }
+}
+
+impl<T: WireCodec> WireCodec for Vec<T> {
+    fn encode(&self, writer: &mut WireWriter) {
+        writer.write_varint(self.len() as u64);
+        for value in self {
+            value.encode(writer);
+        }
+    }
+    fn decode(reader: &mut WireReader) -> Result<Self, Error> {
+        let len: usize = reader.read_len()?;
+        let mut result: Vec<T> = Vec::with_capacity(len);
+        for _ in 0..len {
+            result.push(T::decode(reader)?);
+        }
+        Ok(result)
+    }
+}